@@ -0,0 +1,469 @@
+//! Derive macro for the `config` crate
+//!
+//! `#[derive(FromConfig)]` implements `config::FromConfig` for the struct with named fields.
+//! Each field is loaded from the property with same name.
+//! Fields with `Option<T>` type are optional, other fields are required.
+//!
+//! Field attributes:
+//!
+//! - `#[config(rename = "xmltv-id")]` - property or section name
+//! - `#[config(default)]` - use `Default::default()` if property is not defined
+//! - `#[config(default = 1000)]` - use literal value if property is not defined
+//! - `#[config(optional)]` - same as `#[config(default)]`
+//! - `#[config(required)]` - property is required even for `Option<T>` field
+//! - `#[config(parse = "path::to::fn")]` - custom parser `fn(&Property) -> Result<T, ConfigError>`
+//! - `#[config(section)]` - load field from the nested section with `FromConfig`
+//! - `#[config(section = "service", many)]` - load all nested sections with same name
+//!   into the collection, for example `Vec<T>`
+//! - `#[config(many)]` - load all repeated properties with same name into the collection.
+//!   Collection could be empty unless field is `required`
+//! - `#[config(range(min = 1, max = 8191))]` - `Schema::range` validator for the property
+//!
+//! `Option<T>` field with default value is always `Some`.
+//!
+//! `#[derive(HasSchema)]` implements `config::HasSchema` for the struct with same attributes.
+//! Doc comments of the struct and fields are used as description.
+//! Fields with `Option<T>` type or with default value are not required.
+//! Section fields should implement `HasSchema` too.
+//! Accepted values of the `bool` and `StrictBool` fields are reported in the schema info.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::{
+    Span,
+    TokenStream as TokenStream2,
+};
+use quote::quote;
+use syn::{
+    parse_macro_input,
+    punctuated::Punctuated,
+    ext::IdentExt,
+    Data,
+    DeriveInput,
+    Error,
+    Expr,
+    ExprPath,
+    Fields,
+    GenericArgument,
+    GenericParam,
+    Lifetime,
+    LifetimeParam,
+    Lit,
+    LitStr,
+    PathArguments,
+    Type,
+};
+
+
+/// Value if property is not defined
+enum DefaultValue {
+    None,
+    Trait,
+    Value(Lit),
+}
+
+
+/// Field attributes
+struct Options {
+    name: Option<String>,
+    default: DefaultValue,
+    required: bool,
+    parse: Option<ExprPath>,
+    section: bool,
+    many: bool,
+    range: Option<(Expr, Expr)>,
+}
+
+
+impl Options {
+    fn parse(field: &syn::Field) -> syn::Result<Self> {
+        let mut options = Options {
+            name: None,
+            default: DefaultValue::None,
+            required: false,
+            parse: None,
+            section: false,
+            many: false,
+            range: None,
+        };
+
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("config")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    let value: LitStr = meta.value()?.parse()?;
+                    options.name = Some(value.value());
+                } else if meta.path.is_ident("default") {
+                    options.default = if meta.input.peek(syn::Token![=]) {
+                        DefaultValue::Value(meta.value()?.parse()?)
+                    } else {
+                        DefaultValue::Trait
+                    };
+                } else if meta.path.is_ident("optional") {
+                    options.default = DefaultValue::Trait;
+                } else if meta.path.is_ident("required") {
+                    options.required = true;
+                } else if meta.path.is_ident("parse") {
+                    let value: LitStr = meta.value()?.parse()?;
+                    options.parse = Some(value.parse()?);
+                } else if meta.path.is_ident("section") {
+                    options.section = true;
+                    if meta.input.peek(syn::Token![=]) {
+                        let value: LitStr = meta.value()?.parse()?;
+                        options.name = Some(value.value());
+                    }
+                } else if meta.path.is_ident("many") {
+                    options.many = true;
+                } else if meta.path.is_ident("range") {
+                    let mut min: Expr = syn::parse_quote!(0);
+                    let mut max: Expr = syn::parse_quote!(::std::primitive::usize::MAX);
+                    meta.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("min") {
+                            min = meta.value()?.parse()?;
+                        } else if meta.path.is_ident("max") {
+                            max = meta.value()?.parse()?;
+                        } else {
+                            return Err(meta.error("expected min or max"));
+                        }
+                        Ok(())
+                    })?;
+                    options.range = Some((min, max));
+                } else {
+                    return Err(meta.error("unknown config attribute"));
+                }
+                Ok(())
+            })?;
+        }
+
+        if options.required && ! matches!(options.default, DefaultValue::None) {
+            return Err(Error::new_spanned(field, "required field could not have default value"));
+        }
+
+        if options.many && ! matches!(options.default, DefaultValue::None) {
+            return Err(Error::new_spanned(field, "many attribute could not have default value"));
+        }
+
+        if options.section && options.parse.is_some() {
+            return Err(Error::new_spanned(field, "section could not have custom parser"));
+        }
+
+        if options.section && options.range.is_some() {
+            return Err(Error::new_spanned(field, "section could not have range"));
+        }
+
+        Ok(options)
+    }
+}
+
+
+/// Returns inner type of the `Option<T>`
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let path = match ty {
+        Type::Path(v) if v.qself.is_none() => &v.path,
+        _ => return None,
+    };
+
+    let segment = path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+
+/// Returns `Some(strict)` for the `bool` or `StrictBool` type
+fn bool_kind(ty: &Type) -> Option<bool> {
+    let path = match ty {
+        Type::Path(v) if v.qself.is_none() => &v.path,
+        _ => return None,
+    };
+
+    let segment = path.segments.last()?;
+    if segment.ident == "bool" {
+        Some(false)
+    } else if segment.ident == "StrictBool" {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+
+/// Returns first generic argument of the type. For example `T` for `Vec<T>`
+fn generic_inner(ty: &Type) -> Option<&Type> {
+    let path = match ty {
+        Type::Path(v) if v.qself.is_none() => &v.path,
+        _ => return None,
+    };
+
+    match &path.segments.last()?.arguments {
+        PathArguments::AngleBracketed(args) => args.args.iter().find_map(|v| match v {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+
+/// Returns text of the doc comments
+fn doc_comment(attrs: &[syn::Attribute]) -> String {
+    let mut result = String::new();
+
+    for attr in attrs.iter().filter(|a| a.path().is_ident("doc")) {
+        if let syn::Meta::NameValue(meta) = &attr.meta {
+            if let Expr::Lit(syn::ExprLit { lit: Lit::Str(v), .. }) = &meta.value {
+                let line = v.value();
+                let line = line.trim();
+                if ! line.is_empty() {
+                    if ! result.is_empty() {
+                        result.push(' ');
+                    }
+                    result.push_str(line);
+                }
+            }
+        }
+    }
+
+    result
+}
+
+
+/// Returns property or section name for the field
+fn field_name(field: &syn::Field, options: &Options) -> LitStr {
+    let name = match &options.name {
+        Some(v) => v.clone(),
+        None => field.ident.as_ref().unwrap().unraw().to_string(),
+    };
+    LitStr::new(&name, Span::call_site())
+}
+
+
+/// Returns expression to load field value
+fn load_field(field: &syn::Field) -> syn::Result<TokenStream2> {
+    let options = Options::parse(field)?;
+    let name = field_name(field, &options);
+
+    let missing = quote! {
+        || ::config::ConfigError::MissingProperty(config.get_location(), #name.to_owned())
+    };
+
+    if options.many && options.section {
+        return Ok(quote! {
+            config.iter()
+                .filter(|s| s.get_name() == #name)
+                .map(::config::FromConfig::from_config)
+                .collect::<::std::result::Result<_, ::config::ConfigError>>()?
+        });
+    }
+
+    if options.many {
+        let parse = match &options.parse {
+            Some(v) => quote! { #v },
+            None => quote! { ::config::FromProperty::from_property },
+        };
+
+        let check = if options.required {
+            Some(quote! { config.get_property(#name).ok_or_else(#missing)?; })
+        } else {
+            None
+        };
+
+        return Ok(quote! {{
+            #check
+            config.get_all(#name)
+                .map(#parse)
+                .collect::<::std::result::Result<_, ::config::ConfigError>>()?
+        }});
+    }
+
+    let inner = option_inner(&field.ty);
+
+    let value = if options.section {
+        quote! {
+            config.iter()
+                .find(|s| s.get_name() == #name)
+                .map(::config::FromConfig::from_config)
+                .transpose()?
+        }
+    } else if let Some(parse) = &options.parse {
+        quote! {
+            config.get_property(#name)
+                .map(#parse)
+                .transpose()?
+        }
+    } else {
+        let ty = inner.unwrap_or(&field.ty);
+        quote! { config.try_get::<#ty>(#name)? }
+    };
+
+    let result = match &options.default {
+        DefaultValue::Trait => quote! { #value.unwrap_or_default() },
+        DefaultValue::Value(Lit::Str(v)) => quote! { #value.unwrap_or_else(|| ::std::convert::From::from(#v)) },
+        DefaultValue::Value(v) => quote! { #value.unwrap_or(#v) },
+        DefaultValue::None if inner.is_none() || options.required => quote! { #value.ok_or_else(#missing)? },
+        DefaultValue::None => return Ok(value),
+    };
+
+    // Option with default value or required option is always defined
+    if inner.is_some() {
+        return Ok(quote! { Some(#result) });
+    }
+
+    Ok(result)
+}
+
+
+/// Returns fields of the struct
+fn named_fields<'a>(input: &'a DeriveInput, derive: &str) -> syn::Result<&'a Punctuated<syn::Field, syn::Token![,]>> {
+    if let Data::Struct(data) = &input.data {
+        if let Fields::Named(fields) = &data.fields {
+            return Ok(&fields.named);
+        }
+    }
+
+    Err(Error::new_spanned(input, format!("{} requires struct with named fields", derive)))
+}
+
+
+fn expand_from_config(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = named_fields(&input, "FromConfig")?;
+
+    let mut body = Vec::with_capacity(fields.len());
+    for field in fields {
+        let ident = &field.ident;
+        let value = load_field(field)?;
+        body.push(quote! { #ident: #value });
+    }
+
+    /* use struct lifetime for borrowed values or declare new one */
+    let mut generics = input.generics.clone();
+    let lifetime = match input.generics.lifetimes().next() {
+        Some(v) => v.lifetime.clone(),
+        None => {
+            let lifetime = Lifetime::new("'__config", Span::call_site());
+            generics.params.insert(0, GenericParam::Lifetime(LifetimeParam::new(lifetime.clone())));
+            lifetime
+        }
+    };
+
+    let ident = &input.ident;
+    let (impl_generics, _, _) = generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::config::FromConfig<#lifetime> for #ident #ty_generics #where_clause {
+            fn from_config(config: &#lifetime ::config::Config) -> ::std::result::Result<Self, ::config::ConfigError> {
+                Ok(#ident {
+                    #( #body, )*
+                })
+            }
+        }
+    })
+}
+
+
+/// Returns statement to append field into the schema
+fn schema_field(field: &syn::Field) -> syn::Result<TokenStream2> {
+    let options = Options::parse(field)?;
+    let name = field_name(field, &options);
+    let description = doc_comment(&field.attrs);
+
+    if options.section {
+        let ty = if options.many || option_inner(&field.ty).is_some() {
+            generic_inner(&field.ty)
+                .ok_or_else(|| Error::new_spanned(&field.ty, "expected collection type"))?
+        } else {
+            &field.ty
+        };
+
+        let description = if description.is_empty() {
+            None
+        } else {
+            Some(quote! { nested.set_description(#description); })
+        };
+
+        return Ok(quote! {
+            let mut nested = <#ty as ::config::HasSchema>::schema(#name);
+            #description
+            schema.push(nested);
+        });
+    }
+
+    let validator = match &options.range {
+        Some((min, max)) => quote! { ::config::Schema::range((#min) .. (#max)) },
+        None => quote! { None },
+    };
+
+    if options.many {
+        let min = usize::from(options.required);
+        return Ok(quote! {
+            schema.set_repeatable(#name, #description, #min, usize::MAX, #validator);
+        });
+    }
+
+    let required = options.required || (
+        option_inner(&field.ty).is_none() &&
+        matches!(options.default, DefaultValue::None)
+    );
+
+    let ty = option_inner(&field.ty).unwrap_or(&field.ty);
+    if let (Some(strict), None) = (bool_kind(ty), &options.range) {
+        return Ok(quote! {
+            schema.set_bool(#name, #description, #required, #strict);
+        });
+    }
+
+    Ok(quote! {
+        schema.set(#name, #description, #required, #validator);
+    })
+}
+
+
+fn expand_has_schema(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = named_fields(&input, "HasSchema")?;
+
+    let mut body = Vec::with_capacity(fields.len());
+    for field in fields {
+        body.push(schema_field(field)?);
+    }
+
+    let ident = &input.ident;
+    let description = doc_comment(&input.attrs);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::config::HasSchema for #ident #ty_generics #where_clause {
+            fn schema(name: &str) -> ::config::Schema {
+                let mut schema = ::config::Schema::new(name, #description);
+                #( #body )*
+                schema
+            }
+        }
+    })
+}
+
+
+#[proc_macro_derive(FromConfig, attributes(config))]
+pub fn derive_from_config(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_from_config(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+
+#[proc_macro_derive(HasSchema, attributes(config))]
+pub fn derive_has_schema(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_has_schema(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
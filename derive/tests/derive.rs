@@ -0,0 +1,206 @@
+use config::{
+    Config,
+    ConfigError,
+    FromConfig,
+    Property,
+};
+
+
+const SRC: &str = r#"
+xmltv = /opt/discovery.xml
+
+[multiplex]
+tsid = 1
+mode = dvb-t
+
+[multiplex/service]
+pnr = 1
+xmltv-id = ch1
+caid = 0x0500
+caid = 0x0B00
+
+[multiplex/service]
+pnr = 2
+type = radio
+"#;
+
+
+#[derive(Debug, PartialEq)]
+enum Mode {
+    DvbT,
+    DvbS,
+}
+
+
+fn parse_mode(p: &Property) -> Result<Mode, ConfigError> {
+    match p.get_value() {
+        "dvb-t" => Ok(Mode::DvbT),
+        "dvb-s" => Ok(Mode::DvbS),
+        _ => Err(ConfigError::InvalidProperty(p.get_value_location(), "mode".to_owned())),
+    }
+}
+
+
+#[derive(FromConfig)]
+struct Service<'a> {
+    pnr: u16,
+    #[config(rename = "xmltv-id")]
+    xmltv_id: Option<&'a str>,
+    #[config(default = "tv")]
+    r#type: &'a str,
+    #[config(default = 1000)]
+    buffer: usize,
+    #[config(optional)]
+    enable: bool,
+    #[config(rename = "caid", many)]
+    caids: Vec<u16>,
+}
+
+
+#[derive(FromConfig)]
+struct Multiplex<'a> {
+    tsid: u16,
+    #[config(parse = "parse_mode")]
+    mode: Mode,
+    #[config(section = "service", many)]
+    services: Vec<Service<'a>>,
+}
+
+
+#[derive(FromConfig)]
+struct Main<'a> {
+    xmltv: &'a str,
+    #[config(section)]
+    multiplex: Multiplex<'a>,
+    #[config(section)]
+    output: Option<Output>,
+}
+
+
+#[derive(FromConfig)]
+struct Output {
+    #[config(required)]
+    port: Option<u16>,
+}
+
+
+fn error<T>(result: Result<T, ConfigError>) -> ConfigError {
+    match result {
+        Err(e) => e,
+        Ok(_) => unreachable!(),
+    }
+}
+
+
+#[test]
+fn test_derive() {
+    let config = Config::parse(SRC.as_bytes()).unwrap();
+    let main = Main::from_config(&config).unwrap();
+
+    assert_eq!(main.xmltv, "/opt/discovery.xml");
+    assert!(main.output.is_none());
+
+    let multiplex = &main.multiplex;
+    assert_eq!(multiplex.tsid, 1);
+    assert_eq!(multiplex.mode, Mode::DvbT);
+    assert_eq!(multiplex.services.len(), 2);
+
+    let service = &multiplex.services[0];
+    assert_eq!(service.pnr, 1);
+    assert_eq!(service.xmltv_id, Some("ch1"));
+    assert_eq!(service.r#type, "tv");
+    assert_eq!(service.buffer, 1000);
+    assert!(! service.enable);
+    assert_eq!(service.caids, [0x0500, 0x0B00]);
+
+    let service = &multiplex.services[1];
+    assert_eq!(service.xmltv_id, None);
+    assert_eq!(service.r#type, "radio");
+    assert!(service.caids.is_empty());
+}
+
+
+#[test]
+fn test_derive_missing() {
+    let config = Config::parse("\n[multiplex]\nmode = dvb-t\n".as_bytes()).unwrap();
+    match error(Main::from_config(&config)) {
+        ConfigError::MissingProperty(location, name) => {
+            assert_eq!(location.get_line(), 0);
+            assert_eq!(name, "xmltv");
+        }
+        _ => unreachable!(),
+    }
+
+    let multiplex = config.iter().next().unwrap();
+    match error(Multiplex::from_config(multiplex)) {
+        ConfigError::MissingProperty(location, name) => {
+            assert_eq!(location.get_line(), 2);
+            assert_eq!(name, "tsid");
+        }
+        _ => unreachable!(),
+    }
+
+    let config = Config::parse("[output]\n".as_bytes()).unwrap();
+    let output = config.iter().next().unwrap();
+    assert!(matches!(error(Output::from_config(output)), ConfigError::MissingProperty(..)));
+
+    let config = Config::parse("[output]\nport = 8000\n".as_bytes()).unwrap();
+    let output = Output::from_config(config.iter().next().unwrap()).unwrap();
+    assert_eq!(output.port, Some(8000));
+}
+
+
+#[test]
+fn test_derive_invalid() {
+    let config = Config::parse("[multiplex]\ntsid = 1\nmode = dvb-c\n".as_bytes()).unwrap();
+    let multiplex = config.iter().next().unwrap();
+    match error(Multiplex::from_config(multiplex)) {
+        ConfigError::InvalidProperty(location, name) => {
+            assert_eq!(location.get_line(), 3);
+            assert_eq!(name, "mode");
+        }
+        _ => unreachable!(),
+    }
+
+    let src = "[multiplex]\ntsid = 1\nmode = dvb-s\n[multiplex/service]\npnr = 70000\n";
+    let config = Config::parse(src.as_bytes()).unwrap();
+    let multiplex = config.iter().next().unwrap();
+    match error(Multiplex::from_config(multiplex)) {
+        ConfigError::OutOfRange(location, name) => {
+            assert_eq!(location.get_line(), 5);
+            assert_eq!(location.get_column(), 7);
+            assert_eq!(name, "pnr");
+        }
+        _ => unreachable!(),
+    }
+}
+
+
+#[test]
+fn test_derive_option_default() {
+    #[derive(FromConfig)]
+    struct Input {
+        #[config(default)]
+        pnr: Option<u16>,
+        #[config(optional)]
+        enable: Option<bool>,
+        #[config(default = "udp")]
+        proto: Option<String>,
+        #[config(default = 1316)]
+        size: Option<usize>,
+    }
+
+    let config = Config::parse("".as_bytes()).unwrap();
+    let input = Input::from_config(&config).unwrap();
+    assert_eq!(input.pnr, Some(0));
+    assert_eq!(input.enable, Some(false));
+    assert_eq!(input.proto.as_deref(), Some("udp"));
+    assert_eq!(input.size, Some(1316));
+
+    let config = Config::parse("pnr = 1\nenable = true\nproto = rtp\nsize = 188\n".as_bytes()).unwrap();
+    let input = Input::from_config(&config).unwrap();
+    assert_eq!(input.pnr, Some(1));
+    assert_eq!(input.enable, Some(true));
+    assert_eq!(input.proto.as_deref(), Some("rtp"));
+    assert_eq!(input.size, Some(188));
+}
@@ -0,0 +1,134 @@
+use config::{
+    Config,
+    ConfigError,
+    FromConfig,
+    HasSchema,
+};
+
+
+/// Program in the multiplex
+#[derive(FromConfig, HasSchema)]
+struct Service<'a> {
+    /// Program number
+    #[config(range(min = 1, max = 65535))]
+    pnr: u16,
+    /// Program name
+    name: Option<&'a str>,
+    /// Scramble the program
+    scramble: Option<bool>,
+    /// Elementary stream PIDs
+    #[config(rename = "pid", many, required, range(min = 32, max = 8190))]
+    pids: Vec<u16>,
+}
+
+
+/// DVB multiplex
+#[derive(FromConfig, HasSchema)]
+struct Multiplex<'a> {
+    /// Transport stream identifier
+    #[config(range(max = 0xFFFF))]
+    tsid: u16,
+    /// Buffer size
+    #[config(default = 1000)]
+    buffer: usize,
+    #[config(section = "service", many)]
+    services: Vec<Service<'a>>,
+}
+
+
+#[derive(FromConfig, HasSchema)]
+struct Main<'a> {
+    /// Path to the EPG file
+    #[config(required)]
+    xmltv: Option<&'a str>,
+    /// Multiplexes
+    #[config(section, many)]
+    multiplex: Vec<Multiplex<'a>>,
+}
+
+
+#[test]
+fn test_schema_info() {
+    let mut schema = Main::schema("");
+    assert_eq!(schema.info(), r#"xmltv = Path to the EPG file
+
+[multiplex]
+# Multiplexes
+tsid = Transport stream identifier
+buffer = Buffer size
+
+[multiplex/service]
+# Program in the multiplex
+pnr = Program number
+name = Program name
+scramble = Scramble the program (true/yes/on/1/enabled, false/no/off/0/disabled)
+pid = Elementary stream PIDs
+"#);
+}
+
+
+#[test]
+fn test_schema_check() {
+    let schema = Main::schema("");
+
+    let src = "xmltv = /opt/epg.xml\n[multiplex]\ntsid = 1\n[multiplex/service]\npnr = 1\nscramble = on\npid = 100\npid = 101\n";
+    let config = Config::parse(src.as_bytes()).unwrap();
+    schema.check(&config).unwrap();
+
+    let main = Main::from_config(&config).unwrap();
+    assert_eq!(main.xmltv, Some("/opt/epg.xml"));
+    assert_eq!(main.multiplex[0].tsid, 1);
+    assert_eq!(main.multiplex[0].buffer, 1000);
+    assert_eq!(main.multiplex[0].services[0].pnr, 1);
+    assert_eq!(main.multiplex[0].services[0].name, None);
+    assert_eq!(main.multiplex[0].services[0].scramble, Some(true));
+    assert_eq!(main.multiplex[0].services[0].pids, [100, 101]);
+
+    let config = Config::parse("[multiplex]\ntsid = 1\n".as_bytes()).unwrap();
+    match schema.check(&config) {
+        Err(ConfigError::MissingProperty(_, name)) => assert_eq!(name, "xmltv"),
+        _ => unreachable!(),
+    }
+
+    let src = "xmltv = /opt/epg.xml\n[multiplex]\ntsid = 1\n[multiplex/service]\npnr = 0\npid = 100\n";
+    let config = Config::parse(src.as_bytes()).unwrap();
+    match schema.check(&config) {
+        Err(ConfigError::InvalidProperty(location, name)) => {
+            assert_eq!(location.get_line(), 5);
+            assert_eq!(name, "pnr");
+        }
+        _ => unreachable!(),
+    }
+
+    let src = "xmltv = /opt/epg.xml\n[multiplex]\ntsid = 1\n[multiplex/service]\npnr = 1\nscramble = maybe\npid = 100\n";
+    let config = Config::parse(src.as_bytes()).unwrap();
+    match schema.check(&config) {
+        Err(ConfigError::InvalidProperty(location, name)) => {
+            assert_eq!(location.get_line(), 6);
+            assert_eq!(name, "scramble");
+        }
+        _ => unreachable!(),
+    }
+
+    let src = "xmltv = /opt/epg.xml\n[multiplex]\n";
+    let config = Config::parse(src.as_bytes()).unwrap();
+    match schema.check(&config) {
+        Err(ConfigError::MissingProperty(location, name)) => {
+            assert_eq!(location.get_line(), 2);
+            assert_eq!(name, "tsid");
+        }
+        _ => unreachable!(),
+    }
+
+    let src = "xmltv = /opt/epg.xml\n[multiplex]\ntsid = 1\n[multiplex/service]\npnr = 1\n";
+    let config = Config::parse(src.as_bytes()).unwrap();
+    match schema.check(&config) {
+        Err(ConfigError::MissingProperty(location, name)) => {
+            assert_eq!(location.get_line(), 4);
+            assert_eq!(name, "pid");
+        }
+        _ => unreachable!(),
+    }
+    let service = config.iter().next().unwrap().iter().next().unwrap();
+    assert!(matches!(Service::from_config(service), Err(ConfigError::MissingProperty(..))));
+}
@@ -1,49 +1,328 @@
 use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt,
     fs::File,
+    num::{
+        NonZeroU8,
+        NonZeroI8,
+        NonZeroU16,
+        NonZeroI16,
+        NonZeroU32,
+        NonZeroI32,
+        NonZeroU64,
+        NonZeroI64,
+        NonZeroUsize,
+        NonZeroIsize,
+    },
+    sync::Arc,
     path::Path,
+    ops::Range,
     io::{
         self,
         Read,
-        BufRead,
-        BufReader,
         Write,
         BufWriter,
     },
 };
 
+use crate::document::{
+    Document,
+    NodeId,
+};
+
+use crate::loader::Loader;
+
+use crate::number::{
+    NumberError,
+    parse_number,
+};
+
+use crate::value::{
+    Style,
+    format_value,
+    format_comment,
+    format_array,
+    format_map,
+};
+
 
 #[derive(Debug, Error)]
 #[error_prefix = "Config"]
 pub enum ConfigError {
     #[error_from]
     Io(io::Error),
-    #[error_kind("invalid key '{}' at line {}", 1, 0)]
-    InvalidKey(usize, String),
-    #[error_kind("invalid property '{}' at line {}", 1, 0)]
-    InvalidProperty(usize, String),
-    #[error_kind("invalid format at line {}", 0)]
-    InvalidFormat(usize),
-    #[error_kind("missing required property '{}' at line {}", 1, 0)]
-    MissingProperty(usize, String),
+    #[error_kind("invalid key '{}' at {}", 1, 0)]
+    InvalidKey(Location, String),
+    #[error_kind("invalid property '{}' at {}", 1, 0)]
+    InvalidProperty(Location, String),
+    #[error_kind("invalid format at {}", 0)]
+    InvalidFormat(Location),
+    #[error_kind("missing required property '{}' at {}", 1, 0)]
+    MissingProperty(Location, String),
+    #[error_kind("unknown property '{}' at {}", 1, 0)]
+    UnknownProperty(Location, String),
+    #[error_kind("duplicate property '{}' at {}", 1, 0)]
+    DuplicateProperty(Location, String),
+    #[error_kind("invalid include '{}' at {}", 1, 0)]
+    InvalidInclude(Location, String),
+    #[error_kind("include cycle '{}' at {}", 1, 0)]
+    IncludeCycle(Location, String),
+    #[error_kind("undefined reference '{}' at {}", 1, 0)]
+    UndefinedReference(Location, String),
+    #[error_kind("reference cycle '{}' at {}", 1, 0)]
+    ReferenceCycle(Location, String),
+    #[error_kind("changed item '{}' of the included file at {}", 1, 0)]
+    IncludeChanged(Location, String),
+    #[error_kind("invalid number of '{}' at {}", 1, 0)]
+    InvalidCount(Location, String),
+    #[error_kind("value of '{}' is out of range at {}", 1, 0)]
+    OutOfRange(Location, String),
+    #[error_kind("invalid query '{}'", 0)]
+    InvalidQuery(String),
+    #[error_kind("{}", 0)]
+    Custom(String),
+}
+
+
+impl ConfigError {
+    /// Returns location of the error or `None` for I/O errors
+    pub fn get_location(&self) -> Option<&Location> {
+        match self {
+            ConfigError::Io(_) => None,
+            ConfigError::InvalidQuery(_) => None,
+            ConfigError::Custom(_) => None,
+            ConfigError::InvalidFormat(location) => Some(location),
+            ConfigError::InvalidKey(location, _) |
+            ConfigError::InvalidProperty(location, _) |
+            ConfigError::MissingProperty(location, _) |
+            ConfigError::UnknownProperty(location, _) |
+            ConfigError::DuplicateProperty(location, _) |
+            ConfigError::InvalidCount(location, _) |
+            ConfigError::OutOfRange(location, _) |
+            ConfigError::InvalidInclude(location, _) |
+            ConfigError::IncludeCycle(location, _) |
+            ConfigError::UndefinedReference(location, _) |
+            ConfigError::ReferenceCycle(location, _) |
+            ConfigError::IncludeChanged(location, _) => Some(location),
+        }
+    }
+
+    /// Returns error message without prefix and location.
+    /// Message is defined once in the `error_kind` attribute
+    pub(crate) fn get_message(&self) -> String {
+        let text = self.to_string();
+        let text = text.strip_prefix("Config: ").unwrap_or(&text);
+
+        let suffix = match self.get_location() {
+            Some(location) => format!(" at {}", location),
+            None => String::new(),
+        };
+
+        text.strip_suffix(suffix.as_str()).unwrap_or(text).to_owned()
+    }
 }
 
 
 pub type Result<T> = std::result::Result<T, ConfigError>;
 
 
-pub struct Property {
+/// Location of the config item: file name, line number, column number
+/// and byte offsets of the item in the file
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Location {
+    file: Option<Arc<Path>>,
     line: usize,
-    name: String,
-    value: String,
+    column: usize,
+    span: Range<usize>,
+}
+
+
+impl Location {
+    #[inline]
+    pub(crate) fn new(file: Option<Arc<Path>>, line: usize, column: usize, span: Range<usize>) -> Self {
+        Location { file, line, column, span }
+    }
+
+    /// Returns path to the file or `None` if config parsed from the stream
+    #[inline]
+    pub fn get_file(&self) -> Option<&Path> { self.file.as_deref() }
+
+    /// Returns line number
+    #[inline]
+    pub fn get_line(&self) -> usize { self.line }
+
+    /// Returns column number of the first item symbol, starts from 1.
+    /// Returns 0 if item is not defined in the config file
+    #[inline]
+    pub fn get_column(&self) -> usize { self.column }
+
+    /// Returns byte offsets of the item in the config file
+    #[inline]
+    pub fn get_span(&self) -> Range<usize> { self.span.clone() }
+}
+
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{}:{}", file.display(), self.line),
+            None => write!(f, "line {}", self.line),
+        }
+    }
+}
+
+
+/// Structured property value
+pub enum Value {
+    /// Single value
+    Scalar,
+    /// Inline array `[a, b, c]`. Items have same name as the array property
+    Array(Vec<Property>),
+    /// Inline map `{a = 1, b = 2}`. Items have the key as a name
+    Map(Vec<Property>),
+}
+
+
+pub struct Property {
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+    pub(crate) file: Option<Arc<Path>>,
+    pub(crate) node: Option<NodeId>,
+    pub(crate) name: String,
+    pub(crate) name_span: Range<usize>,
+    pub(crate) value: String,
+    pub(crate) value_column: usize,
+    pub(crate) value_span: Range<usize>,
+    pub(crate) style: Style,
+    pub(crate) comment: Option<String>,
+    pub(crate) structured: Value,
 }
 
 
 impl Property {
+    /// Creates property without location
+    pub(crate) fn new(name: String, value: String) -> Self {
+        Property {
+            line: 0,
+            column: 0,
+            file: None,
+            node: None,
+            name,
+            name_span: 0 .. 0,
+            value,
+            value_column: 0,
+            value_span: 0 .. 0,
+            style: Style::Line,
+            comment: None,
+            structured: Value::Scalar,
+        }
+    }
+
+    /// Creates property without location with `ToProperty` value
+    pub(crate) fn from_value<T: ToProperty>(name: String, value: T) -> Self {
+        let mut property = Property::new(name, String::new());
+        value.to_property(&mut property);
+        property
+    }
+
+    /// Returns property name
+    #[inline]
+    pub fn get_name(&self) -> &str { self.name.as_str() }
+
     #[inline]
     pub fn get_line(&self) -> usize { self.line }
 
+    /// Returns column number of the property name
+    #[inline]
+    pub fn get_column(&self) -> usize { self.column }
+
+    /// Returns path to the file where property is defined
+    #[inline]
+    pub fn get_file(&self) -> Option<&Path> { self.file.as_deref() }
+
+    /// Returns property location from the beginning of the name to the end of the value
+    #[inline]
+    pub fn get_location(&self) -> Location {
+        let span = self.name_span.start .. self.value_span.end;
+        Location::new(self.file.clone(), self.line, self.column, span)
+    }
+
+    /// Returns byte offsets of the property name in the config file
+    #[inline]
+    pub fn get_name_span(&self) -> Range<usize> { self.name_span.clone() }
+
+    /// Returns byte offsets of the property value in the config file.
+    /// For multi-line values includes all lines
+    #[inline]
+    pub fn get_value_span(&self) -> Range<usize> { self.value_span.clone() }
+
+    /// Returns property value location
+    #[inline]
+    pub fn get_value_location(&self) -> Location {
+        Location::new(self.file.clone(), self.line, self.value_column, self.get_value_span())
+    }
+
+    /// Returns property value.
+    /// For inline array or map returns value in the config syntax, for example `[1, 2]`
     #[inline]
     pub fn get_value(&self) -> &str { self.value.as_str() }
+
+    /// Sets property value with `ToProperty`. Property keeps location and trailing comment
+    #[inline]
+    pub fn set_value<T: ToProperty>(&mut self, value: T) { value.to_property(self) }
+
+    /// Sets single value as is
+    pub(crate) fn set_scalar(&mut self, value: String) {
+        self.value = value;
+        self.structured = Value::Scalar;
+        if self.style == Style::Inline {
+            self.style = Style::Line;
+        }
+    }
+
+    /// Returns structured value: items of the inline array or map
+    #[inline]
+    pub fn get_structured(&self) -> &Value { &self.structured }
+
+    /// Sets inline array value
+    pub fn set_array<I, T>(&mut self, items: I)
+    where
+        I: IntoIterator<Item = T>,
+        T: ScalarProperty,
+    {
+        let items: Vec<Property> = items.into_iter()
+            .map(|v| Property::from_value(self.name.clone(), v))
+            .collect();
+
+        self.value = format_array(items.iter().map(Property::get_value));
+        self.structured = Value::Array(items);
+        self.style = Style::Inline;
+    }
+
+    /// Sets inline map value
+    pub fn set_map<I, K, T>(&mut self, items: I)
+    where
+        I: IntoIterator<Item = (K, T)>,
+        K: Into<String>,
+        T: ScalarProperty,
+    {
+        let items: Vec<Property> = items.into_iter()
+            .map(|(k, v)| Property::from_value(k.into(), v))
+            .collect();
+
+        self.value = format_map(items.iter().map(|p| (p.get_name(), p.get_value())));
+        self.structured = Value::Map(items);
+        self.style = Style::Inline;
+    }
+
+    /// Returns trailing comment
+    #[inline]
+    pub fn get_comment(&self) -> Option<&str> { self.comment.as_deref() }
+
+    pub(crate) fn dump<W: Write>(&self, dst: &mut W) -> io::Result<()> {
+        writeln!(dst, "{} = {}", &self.name, format_value(&self.value, &self.style, self.get_comment()))
+    }
 }
 
 
@@ -55,6 +334,42 @@ impl Property {
 /// White-spaces around line and around delimiter ignores.
 /// Each value is a string, without quotes.
 ///
+/// Value could be wrapped into double quotes to keep leading and trailing white-spaces,
+/// control characters or `#` symbol. Example: `name = "  Value\n"`.
+/// Quoted value supports escape sequences: `\n`, `\t`, `\r`, `\0`, `\"`, `\\`,
+/// and `\u{...}` with hexadecimal unicode code point.
+///
+/// Property could be repeated in the section. `Config::get` returns first value,
+/// `Config::get_all` returns all properties with same name in order of definition.
+///
+/// ## Inline arrays and maps
+///
+/// Value wrapped into `[]` is an inline array. Example: `pids = [100, 101, 0x200]`.
+/// Value wrapped into `{}` is an inline map. Example: `langs = {rus = 1, eng = 2}`.
+/// Items are bare or quoted values separated with `,`. Nested arrays and maps are not supported.
+/// Items are available with `Property::get_structured` or `FromProperty` for `Vec<T>`
+/// and `HashMap<String, T>`. Bare value started with `[` or `{` should be quoted.
+///
+/// ## Multi-line values
+///
+/// Bare value could be continued on the next line with trailing `\` symbol.
+/// White-spaces at the beginning of the next line ignores:
+///
+/// ```text
+/// filter = pnr=1 \
+///     pnr=2
+/// ```
+///
+/// Raw multi-line value could be defined with heredoc block.
+/// Value is a lines between `<<TAG` and line with `TAG` only:
+///
+/// ```text
+/// sdp = <<EOF
+/// v=0
+/// o=- 0 0 IN IP4 127.0.0.1
+/// EOF
+/// ```
+///
 /// ## Sections
 ///
 /// Section is a group of properties. The section name should be wraped into `[]` symbols.
@@ -66,11 +381,56 @@ impl Property {
 ///
 /// Comment line should be started with `#`. Example: `# comment`
 ///
+/// Property and section lines could be ended with trailing comment.
+/// Trailing comment starts with `#` after white-space. Example: `tsid = 1 # transport id`.
+/// `#` inside quoted value is not a comment.
+///
+/// ## Include
+///
+/// Content of the other files could be included with `@include` directive.
+/// Example: `@include services/*.conf`. Path is relative to the file with directive
+/// and could contain glob patterns. Files included in alphabetical order.
+/// Sections of the included file are merged into the config at the point of inclusion.
+/// Properties at the beginning of the included file belongs to the current section.
+/// After the included file the current section is restored.
+///
+/// ## Paths
+///
+/// Property with `PathBuf` type is resolved relative to the file where property is defined,
+/// including included files. Leading `~` is expanded to the home directory.
+/// Example: `xmltv = ../epg/discovery.xml`
+///
+/// ## References
+///
+/// Value could contain references to the other properties and to the environment variables:
+///
+/// - `${name}` - value of the property in the current section or in the parent sections,
+///   otherwise value of the environment variable. Example: `output = udp://${HOST_IP}:10000`
+/// - `${section/name}` - value of the property in the first section with absolute path
+/// - `${name:-default}` - default value if reference is not defined or empty
+/// - `$$` - `$` symbol
+///
+/// Heredoc values are not expanded.
+///
+/// ## Formatting
+///
+/// Parsed config keeps original text of the file (see `Document`).
+/// `Config::dump` writes unchanged lines as is with comments and white-spaces,
+/// changed items are updated in place, and new items are appended to the end of the section.
+/// Included files are not saved, so `dump` returns `IncludeChanged` error
+/// if items from included files are changed, removed, or appended.
+///
 pub struct Config {
-    line: usize,
-    name: String,
-    properties: Vec<Property>,
-    nested: Vec<Config>,
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+    pub(crate) span: Range<usize>,
+    pub(crate) file: Option<Arc<Path>>,
+    pub(crate) node: Option<NodeId>,
+    pub(crate) name: String,
+    pub(crate) comment: Option<String>,
+    pub(crate) properties: Vec<Property>,
+    pub(crate) nested: Vec<Config>,
+    pub(crate) documents: Vec<Document>,
 }
 
 
@@ -83,23 +443,50 @@ impl Config {
     {
         Config {
             line: 0,
+            column: 0,
+            span: 0 .. 0,
+            file: None,
+            node: None,
             name: name.into(),
+            comment: None,
             properties: Vec::new(),
             nested: Vec::new(),
+            documents: Vec::new(),
         }
     }
 
-    /// Appends config property
+    /// Appends config property. Value is written with `ToProperty`
     pub fn set<S, T>(&mut self, name: S, value: T)
     where
         S: Into<String>,
-        T: ToString,
+        T: ToProperty,
     {
-        let property = Property {
-            line: 0,
-            name: name.into(),
-            value: value.to_string(),
-        };
+        let property = Property::from_value(name.into(), value);
+        self.properties.push(property);
+    }
+
+    /// Appends property with inline array value
+    pub fn set_array<S, I, T>(&mut self, name: S, items: I)
+    where
+        S: Into<String>,
+        I: IntoIterator<Item = T>,
+        T: ScalarProperty,
+    {
+        let mut property = Property::new(name.into(), String::new());
+        property.set_array(items);
+        self.properties.push(property);
+    }
+
+    /// Appends property with inline map value
+    pub fn set_map<S, I, K, T>(&mut self, name: S, items: I)
+    where
+        S: Into<String>,
+        I: IntoIterator<Item = (K, T)>,
+        K: Into<String>,
+        T: ScalarProperty,
+    {
+        let mut property = Property::new(name.into(), String::new());
+        property.set_map(items);
         self.properties.push(property);
     }
 
@@ -107,6 +494,55 @@ impl Config {
     #[inline]
     pub fn push(&mut self, nested: Config) { self.nested.push(nested) }
 
+    /// Updates value of the first property with `name` or appends new property
+    pub fn replace<S, T>(&mut self, name: S, value: T)
+    where
+        S: Into<String>,
+        T: ToProperty,
+    {
+        let name = name.into();
+        match self.get_property_mut(&name) {
+            Some(property) => property.set_value(value),
+            None => self.set(name, value),
+        }
+    }
+
+    /// Removes first property with `name`
+    pub fn remove(&mut self, name: &str) -> Option<Property> {
+        let index = self.properties.iter().position(|p| p.name == name)?;
+        Some(self.properties.remove(index))
+    }
+
+    /// Removes first nested section with `name`
+    pub fn remove_section(&mut self, name: &str) -> Option<Config> {
+        let index = self.nested.iter().position(|s| s.name == name)?;
+        Some(self.nested.remove(index))
+    }
+
+    /// Keeps only properties for which `f` returns `true`
+    #[inline]
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&Property) -> bool,
+    {
+        self.properties.retain(f)
+    }
+
+    /// Renames first property with `name`. Returns `false` if property is not found
+    pub fn rename<S: Into<String>>(&mut self, name: &str, new_name: S) -> bool {
+        match self.get_property_mut(name) {
+            Some(property) => {
+                property.name = new_name.into();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sets section name
+    #[inline]
+    pub fn set_name<S: Into<String>>(&mut self, name: S) { self.name = name.into() }
+
     /// Returns section name
     #[inline]
     pub fn get_name(&self) -> &str { self.name.as_str() }
@@ -115,15 +551,55 @@ impl Config {
     #[inline]
     pub fn get_line(&self) -> usize { self.line }
 
+    /// Returns path to the file where section is defined
+    #[inline]
+    pub fn get_file(&self) -> Option<&Path> { self.file.as_deref() }
+
+    /// Returns column number of the section path
+    #[inline]
+    pub fn get_column(&self) -> usize { self.column }
+
+    /// Returns byte offsets of the section path in the config file
+    #[inline]
+    pub fn get_span(&self) -> Range<usize> { self.span.clone() }
+
+    /// Returns section location
+    #[inline]
+    pub fn get_location(&self) -> Location {
+        Location::new(self.file.clone(), self.line, self.column, self.get_span())
+    }
+
+    /// Returns trailing comment of the section line
+    #[inline]
+    pub fn get_comment(&self) -> Option<&str> { self.comment.as_deref() }
+
     /// Returns property
     #[inline]
     pub fn get_property(&self, name: &str) -> Option<&Property> {
-        for p in &self.properties {
-            if p.name == name {
-                return Some(&p);
-            }
-        }
-        None
+        self.properties.iter().find(|p| p.name == name)
+    }
+
+    /// Returns all properties with `name` in order of definition
+    #[inline]
+    pub fn get_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Property> {
+        self.properties.iter().filter(move |p| p.name == name)
+    }
+
+    /// Returns typed values of all properties with `name`.
+    /// Returns `InvalidProperty` error if any value could not be converted
+    pub fn try_get_all<'a, F>(&'a self, name: &'a str) -> Result<Vec<F>>
+    where
+        F: FromProperty<'a>,
+    {
+        self.get_all(name)
+            .map(FromProperty::from_property)
+            .collect()
+    }
+
+    /// Returns mutable property
+    #[inline]
+    pub fn get_property_mut(&mut self, name: &str) -> Option<&mut Property> {
+        self.properties.iter_mut().find(|p| p.name == name)
     }
 
     /// Returns property typed value (boolean or numbers)
@@ -136,94 +612,85 @@ impl Config {
             .and_then(|v| FromProperty::from_property(v).ok())
     }
 
-    /// Returns nested sections iterator
+    /// Returns property typed value or `None` if property is not defined.
+    /// Returns `InvalidProperty` error if value could not be converted
     #[inline]
-    pub fn iter(&self) -> impl Iterator<Item = &Config> { self.nested.iter() }
-
-    /// Deserialize config
-    pub fn parse<R: Read>(src: R) -> Result<Config> {
-        let mut line = 0;
-
-        let mut reader = BufReader::new(src);
-        let mut buffer = String::new();
-
-        let mut root = Config::new("");
-        let mut last = &mut root;
-
-        loop {
-            buffer.clear();
-            if reader.read_line(&mut buffer)? == 0 {
-                break
-            }
-
-            line += 1;
-
-            let token = buffer.trim_start();
-            // TODO: `;` - deprecated
-            if token.is_empty() || token.starts_with('#') || token.starts_with(';') {
-                continue;
-            }
-
-            if token.starts_with('[') {
-                /* Config */
-
-                let token = (&token[1 ..]).trim_start(); /* skip [ */
-                let end = token.find(']').ok_or_else(|| ConfigError::InvalidFormat(line))?;
-                let token = (&token[.. end]).trim_end(); /* ignore ] */
-
-                let mut skip = 0;
-                last = &mut root;
-
-                loop {
-                    let next = token[skip ..].find('/').map_or(0, |v| v + skip);
-                    if next == 0 { break }
-                    let item = &token[skip .. next];
-                    skip = next + 1;
-
-                    last = last.nested.last_mut()
-                        .ok_or_else(|| ConfigError::InvalidKey(line, token.to_owned()))?;
-
-                    if last.name != item {
-                        return Err(ConfigError::InvalidKey(line, token.to_owned()));
-                    }
-                }
-
-                let section = Config {
-                    line,
-                    name: token[skip ..].to_owned(),
-                    properties: Vec::new(),
-                    nested: Vec::new(),
-                };
+    pub fn try_get<'a, F>(&'a self, name: &str) -> Result<Option<F>>
+    where
+        F: FromProperty<'a>,
+    {
+        self.get_property(name)
+            .map(FromProperty::from_property)
+            .transpose()
+    }
 
-                last.nested.push(section);
-                last = last.nested.last_mut().unwrap();
+    /// Returns typed value of the required property.
+    /// Returns `MissingProperty` error if property is not defined
+    /// or `InvalidProperty` error if value could not be converted
+    #[inline]
+    pub fn require<'a, F>(&'a self, name: &str) -> Result<F>
+    where
+        F: FromProperty<'a>,
+    {
+        self.try_get(name)?
+            .ok_or_else(|| ConfigError::MissingProperty(self.get_location(), name.to_owned()))
+    }
 
-                continue;
-            }
+    /// Returns nested sections iterator
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &Config> { self.nested.iter() }
 
-            let skip = token.find('=')
-                .ok_or_else(|| ConfigError::InvalidFormat(line))?;
+    /// Returns mutable nested sections iterator
+    #[inline]
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Config> { self.nested.iter_mut() }
 
-            last.properties.push(Property {
-                line,
-                name: (&token[.. skip]).trim_end().to_owned(),
-                value: (&token[skip + 1 ..]).trim().to_owned(),
-            });
-        }
+    /// Returns first mutable nested section with `name`
+    #[inline]
+    pub fn nested_mut(&mut self, name: &str) -> Option<&mut Config> {
+        self.nested.iter_mut().find(|s| s.name == name)
+    }
 
-        Ok(root)
+    /// Deserialize config
+    #[inline]
+    pub fn parse<R: Read>(src: R) -> Result<Config> {
+        Loader::new().parse(src)
     }
 
     /// Opens config file
     #[inline]
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let file = File::open(path)?;
-        Self::parse(file)
+        Loader::new().open(path)
+    }
+
+    /// Deserialize config in the recovery mode.
+    /// Lines with errors are skipped. Returns partial config with all found errors sorted by location
+    ///
+    /// ```
+    /// use config::Config;
+    ///
+    /// let (config, errors) = Config::parse_recover("a = 1\nb\nc = 3\n".as_bytes());
+    /// assert_eq!(config.get("c"), Some(3u8));
+    /// assert_eq!(errors.len(), 1);
+    /// ```
+    #[inline]
+    pub fn parse_recover<R: Read>(src: R) -> (Config, Vec<ConfigError>) {
+        Loader::new().parse_recover(src)
+    }
+
+    /// Opens config file in the recovery mode
+    #[inline]
+    pub fn open_recover<P: AsRef<Path>>(path: P) -> (Config, Vec<ConfigError>) {
+        Loader::new().open_recover(path)
+    }
+
+    pub(crate) fn dump_nested<W: Write>(&self, dst: &mut W, level: &mut String) -> io::Result<()> {
+        writeln!(dst, "\n[{}{}]{}", level, &self.name, format_comment(self.get_comment()))?;
+        self.dump_section(dst, level)
     }
 
     fn dump_section<W: Write>(&self, dst: &mut W, level: &mut String) -> io::Result<()> {
         for p in &self.properties {
-            writeln!(dst, "{} = {}", &p.name, &p.value)?;
+            p.dump(dst)?;
         }
 
         if ! self.nested.is_empty() {
@@ -235,8 +702,7 @@ impl Config {
             }
 
             for s in &self.nested {
-                writeln!(dst, "\n[{}{}]", level, &s.name)?;
-                s.dump_section(dst, level)?;
+                s.dump_nested(dst, level)?;
             }
 
             level.truncate(level_skip);
@@ -245,11 +711,15 @@ impl Config {
         Ok(())
     }
 
-    /// Serializes config
-    #[inline]
+    /// Serializes config.
+    /// Returns `IncludeChanged` error if items from included files are changed
     pub fn dump<W: Write>(&self, dst: &mut W) -> Result<()> {
-        let mut level = String::with_capacity(256);
-        self.dump_section(dst, &mut level)?;
+        if ! self.documents.is_empty() {
+            crate::document::dump_config(&self.documents, self, dst)?;
+        } else {
+            let mut level = String::with_capacity(256);
+            self.dump_section(dst, &mut level)?;
+        }
         Ok(())
     }
 
@@ -269,6 +739,44 @@ pub trait FromProperty<'a>: Sized {
 }
 
 
+/// A trait to write a value into a Property.
+/// Value written with `ToProperty` is parsed back with `FromProperty` into the same value.
+/// Custom types could be written with `Property::set_value` or `Property::set_array`
+///
+/// ```
+/// use config::{Config, Hex};
+///
+/// let mut config = Config::new("");
+/// config.set("pid", Hex(0x1FFFu16));
+/// config.set("pids", vec![Hex(0x100u16), Hex(0x101)]);
+///
+/// let mut s = Vec::<u8>::new();
+/// config.dump(&mut s).unwrap();
+/// assert_eq!(s.as_slice(), b"pid = 0x1FFF\npids = [0x100, 0x101]\n");
+/// ```
+pub trait ToProperty {
+    fn to_property(&self, p: &mut Property);
+}
+
+
+/// A marker for types written as a single value.
+/// Only single values could be items of the inline array or map,
+/// so `ToProperty` of the type should not write inline array or map
+///
+/// ```compile_fail
+/// let mut config = config::Config::new("");
+/// config.set("v", vec![vec![1u8, 2]]);
+/// ```
+pub trait ScalarProperty: ToProperty {}
+
+
+/// A trait to abstract creating a new instance of a type from a config section.
+/// Could be implemented with `#[derive(FromConfig)]` (`derive` feature)
+pub trait FromConfig<'a>: Sized {
+    fn from_config(config: &'a Config) -> Result<Self>;
+}
+
+
 impl<'a> FromProperty<'a> for &'a str {
     #[inline]
     fn from_property(p: &'a Property) -> Result<&'a str> {
@@ -277,25 +785,121 @@ impl<'a> FromProperty<'a> for &'a str {
 }
 
 
+impl<'a> FromProperty<'a> for String {
+    #[inline]
+    fn from_property(p: &'a Property) -> Result<String> {
+        Ok(p.value.clone())
+    }
+}
+
+
+impl<'a> FromProperty<'a> for Box<str> {
+    #[inline]
+    fn from_property(p: &'a Property) -> Result<Box<str>> {
+        Ok(Box::from(p.value.as_str()))
+    }
+}
+
+
+impl<'a> FromProperty<'a> for Cow<'a, str> {
+    #[inline]
+    fn from_property(p: &'a Property) -> Result<Cow<'a, str>> {
+        Ok(Cow::Borrowed(p.value.as_str()))
+    }
+}
+
+
+/// Value should contain exactly one character
+impl<'a> FromProperty<'a> for char {
+    fn from_property(p: &'a Property) -> Result<char> {
+        let mut chars = p.value.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(ConfigError::InvalidProperty(p.get_value_location(), p.name.to_owned())),
+        }
+    }
+}
+
+
+/// Words accepted as `true` value. Case-insensitive
+pub(crate) const TRUE_WORDS: &[&str] = &["true", "yes", "on", "1", "enabled"];
+
+
+/// Words accepted as `false` value. Case-insensitive
+pub(crate) const FALSE_WORDS: &[&str] = &["false", "no", "off", "0", "disabled"];
+
+
+/// Parses boolean value. In the strict mode only `true` and `false` are accepted
+pub(crate) fn parse_bool(value: &str, strict: bool) -> Option<bool> {
+    if strict {
+        return match value {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        };
+    }
+
+    if TRUE_WORDS.iter().any(|v| value.eq_ignore_ascii_case(v)) {
+        Some(true)
+    } else if FALSE_WORDS.iter().any(|v| value.eq_ignore_ascii_case(v)) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+
+/// Boolean value: `true`, `yes`, `on`, `1`, `enabled`
+/// or `false`, `no`, `off`, `0`, `disabled`. Case-insensitive
 impl<'a> FromProperty<'a> for bool {
     #[inline]
     fn from_property(p: &'a Property) -> Result<bool> {
-        let value = p.value.parse::<bool>()
-            .map_err(|_| ConfigError::InvalidProperty(p.line, p.name.to_owned()))?;
-        Ok(value)
+        parse_bool(&p.value, false)
+            .ok_or_else(|| ConfigError::InvalidProperty(p.get_value_location(), p.name.to_owned()))
     }
 }
 
 
+/// Boolean value in the strict mode: only `true` or `false` are accepted
+///
+/// ```
+/// use config::{Config, StrictBool};
+///
+/// let config = Config::parse("debug = yes".as_bytes()).unwrap();
+/// assert_eq!(config.get("debug"), Some(true));
+/// assert_eq!(config.get::<StrictBool>("debug"), None);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StrictBool(pub bool);
+
+
+impl From<StrictBool> for bool {
+    #[inline]
+    fn from(value: StrictBool) -> bool { value.0 }
+}
+
+
+impl<'a> FromProperty<'a> for StrictBool {
+    #[inline]
+    fn from_property(p: &'a Property) -> Result<StrictBool> {
+        parse_bool(&p.value, true)
+            .map(StrictBool)
+            .ok_or_else(|| ConfigError::InvalidProperty(p.get_value_location(), p.name.to_owned()))
+    }
+}
+
+
+/// Integer literal: decimal, `0x` hexadecimal, `0o` octal, or `0b` binary with optional sign
+/// and `_` separators. Returns `OutOfRange` error if number does not fit into the type
 macro_rules! impl_get_number {
     ( $( $t:tt ),* ) => {
         $( impl<'a> FromProperty<'a> for $t {
             #[inline]
             fn from_property(p: &'a Property) -> Result<$t> {
-                let (skip, radix) = if p.value.starts_with("0x") { (2, 16u32) } else { (0, 10u32) };
-                let value = $t::from_str_radix(&p.value[skip ..], radix)
-                    .map_err(|_| ConfigError::InvalidProperty(p.line, p.name.to_owned()))?;
-                Ok(value)
+                parse_number::<$t>(&p.value).map_err(|e| match e {
+                    NumberError::Syntax => ConfigError::InvalidProperty(p.get_value_location(), p.name.to_owned()),
+                    NumberError::Overflow => ConfigError::OutOfRange(p.get_value_location(), p.name.to_owned()),
+                })
             }
         } )*
     };
@@ -303,3 +907,162 @@ macro_rules! impl_get_number {
 
 
 impl_get_number!(u8, i8, u16, i16, u32, i32, u64, i64, usize, isize);
+
+
+macro_rules! impl_get_nonzero {
+    ( $( $t:tt => $n:tt ),* ) => {
+        $( impl<'a> FromProperty<'a> for $t {
+            #[inline]
+            fn from_property(p: &'a Property) -> Result<$t> {
+                $t::new($n::from_property(p)?)
+                    .ok_or_else(|| ConfigError::InvalidProperty(p.get_value_location(), p.name.to_owned()))
+            }
+        } )*
+    };
+}
+
+
+impl_get_nonzero!(
+    NonZeroU8 => u8, NonZeroI8 => i8,
+    NonZeroU16 => u16, NonZeroI16 => i16,
+    NonZeroU32 => u32, NonZeroI32 => i32,
+    NonZeroU64 => u64, NonZeroI64 => i64,
+    NonZeroUsize => usize, NonZeroIsize => isize
+);
+
+
+/// Decimal number with optional fraction and exponent. Example: `1.5`, `-2e-3`, `1E6`
+macro_rules! impl_get_float {
+    ( $( $t:tt ),* ) => {
+        $( impl<'a> FromProperty<'a> for $t {
+            #[inline]
+            fn from_property(p: &'a Property) -> Result<$t> {
+                p.value.parse::<$t>()
+                    .map_err(|_| ConfigError::InvalidProperty(p.get_value_location(), p.name.to_owned()))
+            }
+        } )*
+    };
+}
+
+
+impl_get_float!(f32, f64);
+
+
+/// Inline array `[a, b, c]`
+impl<'a, T: FromProperty<'a>> FromProperty<'a> for Vec<T> {
+    fn from_property(p: &'a Property) -> Result<Vec<T>> {
+        match &p.structured {
+            Value::Array(items) => items.iter().map(T::from_property).collect(),
+            _ => Err(ConfigError::InvalidProperty(p.get_value_location(), p.name.to_owned())),
+        }
+    }
+}
+
+
+/// Inline map `{a = 1, b = 2}`
+impl<'a, T: FromProperty<'a>> FromProperty<'a> for HashMap<String, T> {
+    fn from_property(p: &'a Property) -> Result<HashMap<String, T>> {
+        match &p.structured {
+            Value::Map(items) => items.iter()
+                .map(|item| Ok((item.name.clone(), T::from_property(item)?)))
+                .collect(),
+            _ => Err(ConfigError::InvalidProperty(p.get_value_location(), p.name.to_owned())),
+        }
+    }
+}
+
+
+impl<T: ToProperty + ?Sized> ToProperty for &T {
+    #[inline]
+    fn to_property(&self, p: &mut Property) { T::to_property(self, p) }
+}
+
+
+impl<T: ScalarProperty + ?Sized> ScalarProperty for &T {}
+
+
+/// Implements `ScalarProperty` marker
+macro_rules! impl_scalar {
+    ( $( $t:ty ),* ) => {
+        $( impl ScalarProperty for $t {} )*
+    };
+}
+
+
+impl_scalar!(str, String, Box<str>, Cow<'_, str>, StrictBool);
+
+
+impl ToProperty for str {
+    #[inline]
+    fn to_property(&self, p: &mut Property) { p.set_scalar(self.to_owned()) }
+}
+
+
+impl ToProperty for String {
+    #[inline]
+    fn to_property(&self, p: &mut Property) { p.set_scalar(self.clone()) }
+}
+
+
+impl ToProperty for Box<str> {
+    #[inline]
+    fn to_property(&self, p: &mut Property) { p.set_scalar(self.to_string()) }
+}
+
+
+impl<'a> ToProperty for Cow<'a, str> {
+    #[inline]
+    fn to_property(&self, p: &mut Property) { p.set_scalar(self.to_string()) }
+}
+
+
+impl ToProperty for StrictBool {
+    #[inline]
+    fn to_property(&self, p: &mut Property) { self.0.to_property(p) }
+}
+
+
+/// Types with `Display` that is parsed back with `FromProperty`
+macro_rules! impl_set_display {
+    ( $( $t:tt ),* ) => {
+        $( impl ToProperty for $t {
+            #[inline]
+            fn to_property(&self, p: &mut Property) { p.set_scalar(self.to_string()) }
+        }
+
+        impl ScalarProperty for $t {} )*
+    };
+}
+
+
+impl_set_display!(
+    bool, char,
+    u8, i8, u16, i16, u32, i32, u64, i64, usize, isize,
+    NonZeroU8, NonZeroI8, NonZeroU16, NonZeroI16, NonZeroU32, NonZeroI32,
+    NonZeroU64, NonZeroI64, NonZeroUsize, NonZeroIsize,
+    f32, f64
+);
+
+
+/// Inline array `[a, b, c]`
+impl<T: ScalarProperty> ToProperty for [T] {
+    #[inline]
+    fn to_property(&self, p: &mut Property) { p.set_array(self) }
+}
+
+
+/// Inline array `[a, b, c]`
+impl<T: ScalarProperty> ToProperty for Vec<T> {
+    #[inline]
+    fn to_property(&self, p: &mut Property) { p.set_array(self) }
+}
+
+
+/// Inline map `{a = 1, b = 2}`. Items are sorted by key
+impl<T: ScalarProperty> ToProperty for HashMap<String, T> {
+    fn to_property(&self, p: &mut Property) {
+        let mut items: Vec<(&String, &T)> = self.iter().collect();
+        items.sort_by_key(|(k, _)| *k);
+        p.set_map(items.into_iter().map(|(k, v)| (k.as_str(), v)))
+    }
+}
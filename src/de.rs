@@ -0,0 +1,586 @@
+use serde::de::{
+    self,
+    Deserialize,
+    DeserializeSeed,
+    MapAccess,
+    SeqAccess,
+    Visitor,
+    value::BorrowedStrDeserializer,
+};
+
+use crate::config::{
+    Config,
+    ConfigError,
+    FromProperty,
+    Location,
+    Property,
+    Result,
+    Value,
+};
+
+
+impl de::Error for ConfigError {
+    #[inline]
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        ConfigError::Custom(msg.to_string())
+    }
+
+    /// Location is defined by the section deserializer
+    #[inline]
+    fn missing_field(field: &'static str) -> Self {
+        ConfigError::MissingProperty(Location::default(), field.to_owned())
+    }
+
+    /// Location is defined by the section deserializer
+    #[inline]
+    fn unknown_field(field: &str, _expected: &'static [&'static str]) -> Self {
+        ConfigError::UnknownProperty(Location::default(), field.to_owned())
+    }
+
+    /// Location is defined by the section deserializer
+    #[inline]
+    fn duplicate_field(field: &'static str) -> Self {
+        ConfigError::DuplicateProperty(Location::default(), field.to_owned())
+    }
+}
+
+
+/// Sets location of the last key for the unknown or duplicate property error
+fn check_key<T>(location: Option<Location>, result: Result<T>) -> Result<T> {
+    let location = match location {
+        Some(v) => v,
+        None => return result,
+    };
+
+    match result {
+        Err(ConfigError::UnknownProperty(l, name)) if l == Location::default() => {
+            Err(ConfigError::UnknownProperty(location, name))
+        }
+        Err(ConfigError::DuplicateProperty(l, name)) if l == Location::default() => {
+            Err(ConfigError::DuplicateProperty(location, name))
+        }
+        v => v,
+    }
+}
+
+
+/// Deserializes config into the user type
+///
+/// - properties are mapped to the struct fields
+/// - nested sections are mapped to the nested structs
+/// - repeated sections and properties with same name are mapped to the `Vec<T>`
+/// - inline arrays are mapped to the `Vec<T>`, inline maps are mapped to the maps and structs
+///
+/// ```
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Service {
+///     pnr: u16,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Multiplex {
+///     tsid: u16,
+///     service: Vec<Service>,
+/// }
+///
+/// let src = "[multiplex]\ntsid = 1\n[multiplex/service]\npnr = 1\n[multiplex/service]\npnr = 2\n";
+/// let config = config::Config::parse(src.as_bytes()).unwrap();
+///
+/// let multiplex: Multiplex = config::from_config(config.iter().next().unwrap()).unwrap();
+/// assert_eq!(multiplex.tsid, 1);
+/// assert_eq!(multiplex.service.len(), 2);
+/// ```
+#[inline]
+pub fn from_config<'de, T: Deserialize<'de>>(config: &'de Config) -> Result<T> {
+    T::deserialize(config)
+}
+
+
+/// Property value deserializer
+struct PropertyDeserializer<'de>(&'de Property);
+
+
+impl<'de> PropertyDeserializer<'de> {
+    #[inline]
+    fn invalid(&self) -> ConfigError {
+        ConfigError::InvalidProperty(self.0.get_value_location(), self.0.name.to_owned())
+    }
+
+    /// Replaces error from the visitor with `InvalidProperty`
+    #[inline]
+    fn check<T>(&self, result: Result<T>) -> Result<T> {
+        match result {
+            Err(ConfigError::Custom(_)) => Err(self.invalid()),
+            v => v,
+        }
+    }
+
+    #[inline]
+    fn get<F: FromProperty<'de>>(&self) -> Result<F> {
+        F::from_property(self.0)
+    }
+}
+
+
+macro_rules! deserialize_number {
+    ( $( $method:ident => $visit:ident : $t:ty ),* ) => {
+        $( fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+            let value = self.get::<$t>()?;
+            self.check(visitor.$visit(value))
+        } )*
+    };
+}
+
+
+impl<'de> de::Deserializer<'de> for PropertyDeserializer<'de> {
+    type Error = ConfigError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.check(visitor.visit_borrowed_str(&self.0.value))
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let value = self.get::<bool>()?;
+        self.check(visitor.visit_bool(value))
+    }
+
+    deserialize_number!(
+        deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16,
+        deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64,
+        deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16,
+        deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64,
+        deserialize_f32 => visit_f32: f32,
+        deserialize_f64 => visit_f64: f64
+    );
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let value = self.get::<char>()?;
+        self.check(visitor.visit_char(value))
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.check(visitor.visit_borrowed_str(&self.0.value))
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.check(visitor.visit_borrowed_str(&self.0.value))
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.check(visitor.visit_borrowed_bytes(self.0.value.as_bytes()))
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.check(visitor.visit_borrowed_bytes(self.0.value.as_bytes()))
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if self.0.value.is_empty() {
+            visitor.visit_unit()
+        } else {
+            Err(self.invalid())
+        }
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        PropertiesDeserializer(vec![self.0]).deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match &self.0.structured {
+            Value::Map(items) => {
+                let mut map = InlineMap {
+                    items: items.iter(),
+                    value: None,
+                    location: None,
+                };
+                let result = visitor.visit_map(&mut map);
+                self.check(check_key(map.location, result))
+            }
+            _ => Err(self.invalid()),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(self, _name: &'static str, _fields: &'static [&'static str], visitor: V) -> Result<V::Value> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value> {
+        let value = BorrowedStrDeserializer::<ConfigError>::new(&self.0.value);
+        self.check(visitor.visit_enum(value))
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+}
+
+
+/// Forwards methods to the deserializer of the first item in the group
+macro_rules! forward_to_first {
+    ( $( $method:ident ),* ) => {
+        $( fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+            self.first().$method(visitor)
+        } )*
+    };
+}
+
+
+/// Properties with same name
+struct PropertiesDeserializer<'de>(Vec<&'de Property>);
+
+
+impl<'de> PropertiesDeserializer<'de> {
+    #[inline]
+    fn first(&self) -> PropertyDeserializer<'de> { PropertyDeserializer(self.0[0]) }
+}
+
+
+impl<'de> de::Deserializer<'de> for PropertiesDeserializer<'de> {
+    type Error = ConfigError;
+
+    forward_to_first!(
+        deserialize_any,
+        deserialize_bool,
+        deserialize_i8, deserialize_i16, deserialize_i32, deserialize_i64,
+        deserialize_u8, deserialize_u16, deserialize_u32, deserialize_u64,
+        deserialize_f32, deserialize_f64,
+        deserialize_char, deserialize_str, deserialize_string,
+        deserialize_bytes, deserialize_byte_buf,
+        deserialize_unit,
+        deserialize_map, deserialize_identifier
+    );
+
+    /// Option wraps all items, e.g. `Option<Vec<T>>`
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(self, name: &'static str, visitor: V) -> Result<V::Value> {
+        self.first().deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// Single property with inline array is deserialized as array items
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if let [p] = self.0.as_slice() {
+            if let Value::Array(items) = &p.structured {
+                let iter = items.iter().map(PropertyDeserializer);
+                return visitor.visit_seq(Sequence(iter));
+            }
+        }
+
+        let iter = self.0.into_iter().map(PropertyDeserializer);
+        visitor.visit_seq(Sequence(iter))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(self, name: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value> {
+        self.first().deserialize_struct(name, fields, visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, name: &'static str, variants: &'static [&'static str], visitor: V) -> Result<V::Value> {
+        self.first().deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+}
+
+
+/// Nested sections with same name
+struct SectionsDeserializer<'de>(Vec<&'de Config>);
+
+
+impl<'de> SectionsDeserializer<'de> {
+    #[inline]
+    fn first(&self) -> &'de Config { self.0[0] }
+}
+
+
+impl<'de> de::Deserializer<'de> for SectionsDeserializer<'de> {
+    type Error = ConfigError;
+
+    forward_to_first!(
+        deserialize_any,
+        deserialize_bool,
+        deserialize_i8, deserialize_i16, deserialize_i32, deserialize_i64,
+        deserialize_u8, deserialize_u16, deserialize_u32, deserialize_u64,
+        deserialize_f32, deserialize_f64,
+        deserialize_char, deserialize_str, deserialize_string,
+        deserialize_bytes, deserialize_byte_buf,
+        deserialize_unit,
+        deserialize_map, deserialize_identifier
+    );
+
+    /// Option wraps all items, e.g. `Option<Vec<T>>`
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(self, name: &'static str, visitor: V) -> Result<V::Value> {
+        self.first().deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_seq(Sequence(self.0.into_iter()))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(self, name: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value> {
+        self.first().deserialize_struct(name, fields, visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, name: &'static str, variants: &'static [&'static str], visitor: V) -> Result<V::Value> {
+        self.first().deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+}
+
+
+/// Sequence of the properties or sections
+struct Sequence<I>(I);
+
+
+impl<'de, I, D> SeqAccess<'de> for Sequence<I>
+where
+    I: Iterator<Item = D>,
+    D: de::Deserializer<'de, Error = ConfigError>,
+{
+    type Error = ConfigError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        match self.0.next() {
+            Some(item) => seed.deserialize(item).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        match self.0.size_hint() {
+            (lower, Some(upper)) if lower == upper => Some(upper),
+            _ => None,
+        }
+    }
+}
+
+
+/// Items of the inline map
+struct InlineMap<'de> {
+    items: std::slice::Iter<'de, Property>,
+    value: Option<&'de Property>,
+    /// Location of the last key
+    location: Option<Location>,
+}
+
+
+impl<'de> MapAccess<'de> for InlineMap<'de> {
+    type Error = ConfigError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        match self.items.next() {
+            Some(item) => {
+                self.value = Some(item);
+                self.location = Some(item.get_location());
+                let key = BorrowedStrDeserializer::<ConfigError>::new(&item.name);
+                seed.deserialize(key).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        match self.value.take() {
+            Some(item) => seed.deserialize(PropertyDeserializer(item)),
+            None => Err(de::Error::custom("value is missing")),
+        }
+    }
+}
+
+
+/// Section item: properties or nested sections with same name
+enum Entry<'de> {
+    Properties(Vec<&'de Property>),
+    Sections(Vec<&'de Config>),
+}
+
+
+/// Section items grouped by name
+struct Section<'de> {
+    entries: std::vec::IntoIter<(&'de str, Entry<'de>)>,
+    value: Option<Entry<'de>>,
+    /// Location of the last key
+    location: Option<Location>,
+}
+
+
+impl<'de> Section<'de> {
+    fn new(config: &'de Config) -> Self {
+        let mut entries: Vec<(&'de str, Entry<'de>)> = Vec::new();
+
+        for p in &config.properties {
+            let item = entries.iter_mut().find_map(|(name, entry)| match entry {
+                Entry::Properties(list) if *name == p.name => Some(list),
+                _ => None,
+            });
+            match item {
+                Some(list) => list.push(p),
+                None => entries.push((&p.name, Entry::Properties(vec![p]))),
+            }
+        }
+
+        for s in &config.nested {
+            let item = entries.iter_mut().find_map(|(name, entry)| match entry {
+                Entry::Sections(list) if *name == s.name => Some(list),
+                _ => None,
+            });
+            match item {
+                Some(list) => list.push(s),
+                None => entries.push((&s.name, Entry::Sections(vec![s]))),
+            }
+        }
+
+        Section {
+            entries: entries.into_iter(),
+            value: None,
+            location: None,
+        }
+    }
+}
+
+
+impl<'de> MapAccess<'de> for Section<'de> {
+    type Error = ConfigError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        match self.entries.next() {
+            Some((name, entry)) => {
+                self.location = Some(match &entry {
+                    Entry::Properties(list) => list[0].get_location(),
+                    Entry::Sections(list) => list[0].get_location(),
+                });
+                self.value = Some(entry);
+                let key = BorrowedStrDeserializer::<ConfigError>::new(name);
+                seed.deserialize(key).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        match self.value.take() {
+            Some(Entry::Properties(list)) => seed.deserialize(PropertiesDeserializer(list)),
+            Some(Entry::Sections(list)) => seed.deserialize(SectionsDeserializer(list)),
+            None => Err(de::Error::custom("value is missing")),
+        }
+    }
+}
+
+
+/// Sets section location for the missing property error
+/// and property location for the unknown or duplicate property error.
+/// Error from the visitor of the nested section is replaced with `InvalidProperty`
+fn check_section<T>(config: &Config, key: Option<Location>, result: Result<T>) -> Result<T> {
+    match check_key(key, result) {
+        Err(ConfigError::MissingProperty(location, name)) if location == Location::default() => {
+            Err(ConfigError::MissingProperty(config.get_location(), name))
+        }
+        Err(ConfigError::Custom(_)) if ! config.name.is_empty() => {
+            Err(ConfigError::InvalidProperty(config.get_location(), config.name.to_owned()))
+        }
+        v => v,
+    }
+}
+
+
+/// Section deserializer. Section could be deserialized into the struct or map
+impl<'de> de::Deserializer<'de> for &'de Config {
+    type Error = ConfigError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_map(visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct enum identifier
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let mut section = Section::new(self);
+        let result = visitor.visit_map(&mut section);
+        check_section(self, section.location, result)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(self, _name: &'static str, _fields: &'static [&'static str], visitor: V) -> Result<V::Value> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+}
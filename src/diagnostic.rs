@@ -0,0 +1,214 @@
+use std::fmt;
+
+use crate::config::{
+    ConfigError,
+    Location,
+};
+
+
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+
+/// Error report with source snippet
+///
+/// Shows the error message, location, the offending line with caret underline,
+/// surrounding lines and hint how to fix the error.
+///
+/// ```
+/// use config::{
+///     Config,
+///     Diagnostic,
+/// };
+///
+/// let src = "[multiplex]\n[wrong/level]\n";
+/// let error = Config::parse(src.as_bytes()).err().unwrap();
+/// let report = Diagnostic::new(&error, src).to_string();
+/// assert_eq!(report, "error: invalid key 'wrong/level'
+///  --> line 2:2
+///   |
+/// 1 | [multiplex]
+/// 2 | [wrong/level]
+///   |  ^^^^^^^^^^^
+///   = hint: nested section must follow its parent [wrong]
+/// ");
+/// ```
+pub struct Diagnostic<'a> {
+    error: &'a ConfigError,
+    source: &'a str,
+    context: usize,
+    color: bool,
+}
+
+
+/// Returns hint how to fix the error
+fn hint(error: &ConfigError) -> Option<String> {
+    match error {
+        ConfigError::Io(_) => None,
+        ConfigError::Custom(_) => None,
+        ConfigError::InvalidQuery(_) => {
+            Some("expected path like `section/nested[key=value]/name`".to_owned())
+        }
+        ConfigError::InvalidKey(_, path) => {
+            let parent = &path[.. path.rfind('/')?];
+            Some(format!("nested section must follow its parent [{}]", parent))
+        }
+        ConfigError::InvalidProperty(_, _) => {
+            Some("value has wrong type or format".to_owned())
+        }
+        ConfigError::InvalidFormat(_) => {
+            Some("expected `name = value`, `[section]` or `@include pattern`".to_owned())
+        }
+        ConfigError::MissingProperty(_, name) => {
+            Some(format!("add property `{} = ...` to the section", name))
+        }
+        ConfigError::UnknownProperty(_, name) => {
+            Some(format!("remove '{}' or check spelling of the name", name))
+        }
+        ConfigError::DuplicateProperty(_, name) => {
+            Some(format!("property '{}' should be defined once", name))
+        }
+        ConfigError::OutOfRange(_, _) => {
+            Some("number does not fit into the property type".to_owned())
+        }
+        ConfigError::InvalidCount(_, name) => {
+            Some(format!("check number of the '{}' properties", name))
+        }
+        ConfigError::InvalidInclude(_, _) => {
+            Some("file not found or pattern is invalid".to_owned())
+        }
+        ConfigError::IncludeCycle(_, path) => {
+            Some(format!("file '{}' already included", path))
+        }
+        ConfigError::UndefinedReference(_, name) => {
+            Some(format!("define property or environment variable '{}' or use default value `${{{}:-...}}`", name, name))
+        }
+        ConfigError::ReferenceCycle(_, name) => {
+            Some(format!("value of the '{}' refers to itself", name))
+        }
+        ConfigError::IncludeChanged(_, _) => {
+            Some("included files are not saved, edit the included file instead".to_owned())
+        }
+    }
+}
+
+
+impl<'a> Diagnostic<'a> {
+    /// Creates new report for the `error` in the `source` text
+    pub fn new(error: &'a ConfigError, source: &'a str) -> Self {
+        Diagnostic {
+            error,
+            source,
+            context: 1,
+            color: false,
+        }
+    }
+
+    /// Sets number of lines to show before and after the offending line. Default: 1
+    #[inline]
+    pub fn set_context(&mut self, context: usize) { self.context = context }
+
+    /// Enables ANSI colors
+    #[inline]
+    pub fn set_color(&mut self, color: bool) { self.color = color }
+
+    #[inline]
+    fn paint(&self, color: &'static str) -> &'static str {
+        if self.color { color } else { "" }
+    }
+
+    /// Returns width of the line numbers column
+    fn gutter_width(&self, location: &Location) -> usize {
+        let last = (location.get_line() + self.context).min(self.source.lines().count());
+        last.max(location.get_line()).to_string().len()
+    }
+
+    fn fmt_location(&self, f: &mut fmt::Formatter, location: &Location) -> fmt::Result {
+        let blue = self.paint(BLUE);
+        let reset = self.paint(RESET);
+
+        let line = location.get_line();
+        let column = location.get_column();
+
+        write!(f, "{}{}-->{} ", blue, " ".repeat(self.gutter_width(location)), reset)?;
+        match location.get_file() {
+            Some(file) => write!(f, "{}:{}", file.display(), line)?,
+            None => write!(f, "line {}", line)?,
+        }
+        if column != 0 {
+            write!(f, ":{}", column)?;
+        }
+        writeln!(f)?;
+
+        let lines: Vec<&str> = self.source.lines().collect();
+        if line == 0 || line > lines.len() {
+            return Ok(());
+        }
+
+        let first = line.saturating_sub(self.context).max(1);
+        let last = (line + self.context).min(lines.len());
+        let width = self.gutter_width(location);
+        let gutter = " ".repeat(width);
+
+        writeln!(f, "{}{} |{}", blue, gutter, reset)?;
+
+        for n in first ..= last {
+            let text = lines[n - 1].trim_end_matches('\r');
+            writeln!(f, "{}{:>width$} |{} {}", blue, n, reset, text, width = width)?;
+
+            if n != line || column == 0 {
+                continue;
+            }
+
+            /* keep tabs to align caret with the token */
+            let mut chars = text.chars();
+            let prefix: String = chars.by_ref()
+                .take(column - 1)
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+
+            let span = location.get_span();
+            let len = match self.source.get(span) {
+                Some(token) => token.lines().next().unwrap_or("").chars().count(),
+                None => 0,
+            };
+            let len = len.min(chars.count()).max(1);
+
+            writeln!(f, "{}{} |{} {}{}{}{}",
+                blue, gutter, reset,
+                prefix, self.paint(RED), "^".repeat(len), reset)?;
+        }
+
+        Ok(())
+    }
+}
+
+
+impl<'a> fmt::Display for Diagnostic<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bold = self.paint(BOLD);
+        let reset = self.paint(RESET);
+
+        writeln!(f, "{}error{}: {}{}{}",
+            self.paint(RED), reset,
+            bold, self.error.get_message(), reset)?;
+
+        if let Some(location) = self.error.get_location() {
+            self.fmt_location(f, location)?;
+        }
+
+        if let Some(hint) = hint(self.error) {
+            let width = match self.error.get_location() {
+                Some(location) => self.gutter_width(location),
+                None => 1,
+            };
+            writeln!(f, "{}{} ={} {}hint{}: {}",
+                self.paint(BLUE), " ".repeat(width), reset,
+                bold, reset, hint)?;
+        }
+
+        Ok(())
+    }
+}
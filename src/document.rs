@@ -0,0 +1,759 @@
+use std::{
+    collections::{
+        BTreeMap,
+        HashMap,
+    },
+    fs::File,
+    sync::{
+        Arc,
+        atomic::{
+            AtomicUsize,
+            Ordering,
+        },
+    },
+    path::Path,
+    ops::Range,
+    io::{
+        self,
+        Read,
+        BufRead,
+        BufReader,
+        Write,
+        BufWriter,
+    },
+};
+
+use crate::config::{
+    Config,
+    ConfigError,
+    Location,
+    Property,
+    Result,
+};
+
+use crate::loader::Loader;
+
+use crate::value::{
+    Style,
+    Inline,
+    heredoc_tag,
+    is_inline,
+    split_comment,
+    parse_value,
+    parse_inline,
+    format_value,
+};
+
+
+/// Syntax element of the config file
+pub(crate) enum Kind {
+    /// Empty line or comment line
+    Blank,
+    /// Section declaration. `range` is a section path location in the node text
+    Section {
+        path: String,
+        range: Range<usize>,
+        comment: Option<String>,
+    },
+    /// Property. `name` and `value` ranges are the locations in the node text.
+    /// For multi-line values `value` range includes all lines and trailing comment.
+    /// `inline` - items of the inline array or map
+    Property {
+        name: String,
+        name_range: Range<usize>,
+        value: String,
+        value_range: Range<usize>,
+        style: Style,
+        comment: Option<String>,
+        inline: Option<Inline>,
+    },
+    /// Include directive with path or glob pattern. `range` is a pattern location in the node text
+    Include {
+        pattern: String,
+        range: Range<usize>,
+    },
+}
+
+
+/// Node identifier: unique identifier of the document,
+/// index of the document in the config, and index of the node in the document
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct NodeId {
+    pub origin: usize,
+    pub document: usize,
+    pub index: usize,
+}
+
+
+/// Returns unique identifier for the new document
+fn next_document_id() -> usize {
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+
+/// One or more lines of the config file with original text
+pub(crate) struct Node {
+    /// Line number where node begins
+    pub line: usize,
+    /// Byte offset of the node in the file
+    pub offset: usize,
+    /// Original text including line endings
+    pub text: String,
+    /// Section declaration node which contains this node.
+    /// For section declaration - parent section declaration.
+    /// `None` for the root section
+    pub owner: Option<NodeId>,
+    pub kind: Kind,
+}
+
+
+impl Node {
+    /// Returns location of the `range` in the node text
+    pub fn location(&self, file: &Option<Arc<Path>>, range: Range<usize>) -> Location {
+        let head = &self.text[.. range.start];
+        let (line, begin) = match head.rfind('\n') {
+            Some(v) => (self.line + head.matches('\n').count(), v + 1),
+            None => (self.line, 0),
+        };
+        let column = head[begin ..].chars().count() + 1;
+        let span = self.offset + range.start .. self.offset + range.end;
+
+        Location::new(file.clone(), line, column, span)
+    }
+
+    /// Returns location of the line content without leading white-spaces and line ending
+    fn line_location(&self, file: &Option<Arc<Path>>, skip: usize) -> Location {
+        let text = trim_eol(&self.text[skip ..]);
+        let token = text.trim();
+        let begin = offset(&self.text, token);
+        self.location(file, begin .. begin + token.len())
+    }
+}
+
+
+/// Lossless representation of the config file
+///
+/// Keeps comments, white-spaces, ordering and original spelling of the each line.
+/// `Config` built from the document keeps reference to the document nodes,
+/// so `Config::dump` changes only lines modified in the config.
+pub struct Document {
+    /// Unique identifier to bind config items only to the nodes of this document
+    pub(crate) id: usize,
+    pub(crate) file: Option<Arc<Path>>,
+    pub(crate) nodes: Vec<Node>,
+}
+
+
+/// Returns offset of the `part` in the `base`. `part` should be a sub-slice of the `base`
+#[inline]
+fn offset(base: &str, part: &str) -> usize {
+    part.as_ptr() as usize - base.as_ptr() as usize
+}
+
+
+/// Returns text without line ending
+#[inline]
+fn trim_eol(text: &str) -> &str {
+    text.trim_end_matches('\n').trim_end_matches('\r')
+}
+
+
+fn parse_section(location: &Location, text: &str) -> Result<Kind> {
+    let token = text.trim_start();
+    let token = token[1 ..].trim_start(); /* skip [ */
+    let end = token.find(']').ok_or_else(|| ConfigError::InvalidFormat(location.clone()))?;
+
+    let (tail, comment) = split_comment(location, &token[end + 1 ..])?;
+    if ! tail.is_empty() {
+        return Err(ConfigError::InvalidFormat(location.clone()));
+    }
+
+    let token = token[.. end].trim_end(); /* ignore ] */
+    let skip = offset(text, token);
+
+    Ok(Kind::Section {
+        path: token.to_owned(),
+        range: skip .. skip + token.len(),
+        comment: comment.map(str::to_owned),
+    })
+}
+
+
+fn parse_directive(location: &Location, text: &str) -> Result<Kind> {
+    let token = text.trim();
+    let skip = token.find(char::is_whitespace).unwrap_or(token.len());
+
+    match &token[.. skip] {
+        "@include" => {
+            let (token, _) = split_comment(location, &token[skip ..])?;
+            let pattern = parse_value(location, token)?;
+            if pattern.is_empty() {
+                return Err(ConfigError::InvalidFormat(location.clone()));
+            }
+            let skip = offset(text, token);
+            Ok(Kind::Include {
+                pattern,
+                range: skip .. skip + token.len(),
+            })
+        }
+        _ => Err(ConfigError::InvalidFormat(location.clone())),
+    }
+}
+
+
+/// Parses property node. Reads following lines of the multi-line value into the node text
+fn parse_property<R: BufRead>(reader: &mut R, node: &mut Node, line: &mut usize, file: &Option<Arc<Path>>) -> Result<Kind> {
+    let location = node.line_location(file, 0);
+    let mut buffer = node.text.clone();
+    let token = buffer.trim_start();
+
+    let skip = token.find('=')
+        .ok_or_else(|| ConfigError::InvalidFormat(location.clone()))?;
+
+    let name = token[.. skip].trim_end();
+    let name_skip = offset(&buffer, name);
+    let name_range = name_skip .. name_skip + name.len();
+    let name = name.to_owned();
+
+    let token = &token[skip + 1 ..];
+    let location = node.line_location(file, offset(&buffer, token));
+    let (token, comment) = split_comment(&location, token)?;
+    let value_skip = offset(&buffer, token);
+    let value_end = value_skip + token.len();
+    let mut comment = comment.map(str::to_owned);
+    let mut inline = None;
+
+    let (value, style) = if let Some(tag) = heredoc_tag(token) {
+        let tag = tag.to_owned();
+        let mut value = String::new();
+
+        loop {
+            buffer.clear();
+            if reader.read_line(&mut buffer)? == 0 {
+                return Err(ConfigError::InvalidFormat(location));
+            }
+
+            *line += 1;
+            node.text.push_str(&buffer);
+
+            let text = trim_eol(&buffer);
+            if text.trim() == tag {
+                break;
+            }
+
+            if *line != node.line + 1 {
+                value.push('\n');
+            }
+            value.push_str(text);
+        }
+
+        (value, Style::Heredoc(tag))
+    } else if is_inline(token) {
+        inline = Some(parse_inline(&location, token, value_skip)?);
+        (token.to_owned(), Style::Inline)
+    } else if token.ends_with('\\') && ! token.starts_with('"') {
+        let mut value = token.to_owned();
+        let mut breaks = Vec::new();
+
+        while value.ends_with('\\') {
+            value.pop();
+            breaks.push(value.len());
+
+            buffer.clear();
+            if reader.read_line(&mut buffer)? == 0 {
+                break;
+            }
+
+            *line += 1;
+            let skip = node.text.len();
+            node.text.push_str(&buffer);
+
+            let location = node.line_location(file, skip);
+            let (token, tail) = split_comment(&location, &buffer)?;
+            value.push_str(token);
+            if tail.is_some() {
+                comment = tail.map(str::to_owned);
+            }
+        }
+
+        (value, Style::Continuation(breaks))
+    } else {
+        (parse_value(&location, token)?, Style::Line)
+    };
+
+    let value_range = match style {
+        Style::Line | Style::Inline => value_skip .. value_end,
+        _ => value_skip .. trim_eol(&node.text).len(),
+    };
+
+    Ok(Kind::Property {
+        name,
+        name_range,
+        value,
+        value_range,
+        style,
+        comment,
+        inline,
+    })
+}
+
+
+impl Document {
+    /// Parses config file. Syntax errors are appended to the `errors`,
+    /// lines with errors are kept in the document as is
+    pub(crate) fn read<R: Read>(src: R, file: Option<Arc<Path>>, errors: &mut Vec<ConfigError>) -> Document {
+        let mut line = 0;
+        let mut skip = 0;
+
+        let mut reader = BufReader::new(src);
+        let mut buffer = String::new();
+
+        let mut nodes = Vec::new();
+
+        loop {
+            buffer.clear();
+            match reader.read_line(&mut buffer) {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(e) => {
+                    errors.push(e.into());
+                    break;
+                }
+            }
+
+            line += 1;
+
+            let mut node = Node {
+                line,
+                offset: skip,
+                text: buffer.clone(),
+                owner: None,
+                kind: Kind::Blank,
+            };
+            let location = node.line_location(&file, 0);
+
+            let token = buffer.trim_start();
+            // TODO: `;` - deprecated
+            let result = if token.is_empty() || token.starts_with('#') || token.starts_with(';') {
+                Ok(Kind::Blank)
+            } else if token.starts_with('[') {
+                parse_section(&location, &node.text)
+            } else if token.starts_with('@') {
+                parse_directive(&location, &node.text)
+            } else {
+                parse_property(&mut reader, &mut node, &mut line, &file)
+            };
+
+            skip += node.text.len();
+
+            match result {
+                Ok(kind) => node.kind = kind,
+                Err(ConfigError::Io(e)) => {
+                    errors.push(ConfigError::Io(e));
+                    nodes.push(node);
+                    break;
+                }
+                Err(e) => errors.push(e),
+            }
+
+            nodes.push(node);
+        }
+
+        Document {
+            id: next_document_id(),
+            file,
+            nodes,
+        }
+    }
+
+    fn parse_file<R: Read>(src: R, file: Option<Arc<Path>>) -> Result<Document> {
+        let mut errors = Vec::new();
+        let document = Self::read(src, file, &mut errors);
+        match errors.into_iter().next() {
+            Some(e) => Err(e),
+            None => Ok(document),
+        }
+    }
+
+    /// Parses config file
+    #[inline]
+    pub fn parse<R: Read>(src: R) -> Result<Document> {
+        Self::parse_file(src, None)
+    }
+
+    /// Opens config file
+    #[inline]
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)?;
+        Self::parse_file(file, Some(Arc::from(path)))
+    }
+
+    /// Returns path to the file or `None` if document parsed from the stream
+    #[inline]
+    pub fn get_file(&self) -> Option<&Path> { self.file.as_deref() }
+
+    /// Builds config tree with default `Loader`.
+    /// Config keeps the document to save changes with minimal difference
+    #[inline]
+    pub fn into_config(self) -> Result<Config> {
+        Loader::new().load(self)
+    }
+
+    /// Writes original text of the document
+    pub fn dump<W: Write>(&self, dst: &mut W) -> Result<()> {
+        for node in &self.nodes {
+            dst.write_all(node.text.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Saves document into file
+    #[inline]
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let file = File::create(path)?;
+        let mut writer = BufWriter::new(file);
+        self.dump(&mut writer)
+    }
+}
+
+
+enum Claim<'a> {
+    Section(String),
+    Property(&'a Property),
+}
+
+
+/// New items of the section
+struct Fresh<'a> {
+    node: Option<NodeId>,
+    path: String,
+    depth: usize,
+    properties: Vec<&'a Property>,
+    nested: Vec<&'a Config>,
+}
+
+
+impl<'a> Fresh<'a> {
+    fn dump_properties<W: Write>(&self, dst: &mut W) -> io::Result<()> {
+        for p in &self.properties {
+            p.dump(dst)?;
+        }
+        Ok(())
+    }
+
+    fn dump_nested<W: Write>(&self, dst: &mut W) -> io::Result<()> {
+        let mut level = self.path.clone();
+        if ! level.is_empty() {
+            level.push('/');
+        }
+
+        for s in &self.nested {
+            s.dump_nested(dst, &mut level)?;
+        }
+        Ok(())
+    }
+}
+
+
+/// Writes config into the main document with original formatting of the unchanged items
+struct Merge<'a> {
+    documents: &'a [Document],
+    claims: HashMap<NodeId, Claim<'a>>,
+    sections: Vec<Fresh<'a>>,
+}
+
+
+impl<'a> Merge<'a> {
+    #[inline]
+    fn node(&self, id: NodeId) -> &'a Node {
+        &self.documents[id.document].nodes[id.index]
+    }
+
+    /// Returns identifier of the node in the main document
+    #[inline]
+    fn main_id(&self, index: usize) -> NodeId {
+        NodeId { origin: self.documents[0].id, document: 0, index }
+    }
+
+    /// Checks if item could be bound to the node.
+    /// Node should belong to the same document, have same owner and kind
+    fn is_claimable(&self, id: NodeId, owner: Option<NodeId>, section: bool) -> bool {
+        let node = match self.documents.get(id.document) {
+            Some(document) if document.id == id.origin => match document.nodes.get(id.index) {
+                Some(v) => v,
+                None => return false,
+            },
+            _ => return false,
+        };
+
+        let kind = match node.kind {
+            Kind::Section { .. } => section,
+            Kind::Property { .. } => ! section,
+            _ => false,
+        };
+
+        kind && node.owner == owner && ! self.claims.contains_key(&id)
+    }
+
+    /// Binds config items to the document nodes.
+    /// Items without node or moved to another section are collected as a new items.
+    /// Returns error on new items of the sections from included files
+    fn claim(&mut self, config: &'a Config, path: String, depth: usize) -> Result<()> {
+        let mut fresh = Fresh {
+            node: config.node,
+            path,
+            depth,
+            properties: Vec::new(),
+            nested: Vec::new(),
+        };
+
+        for p in &config.properties {
+            match p.node {
+                Some(id) if self.is_claimable(id, config.node, false) => {
+                    self.claims.insert(id, Claim::Property(p));
+                }
+                _ => fresh.properties.push(p),
+            }
+        }
+
+        for s in &config.nested {
+            let path = if fresh.path.is_empty() {
+                s.name.clone()
+            } else {
+                format!("{}/{}", &fresh.path, &s.name)
+            };
+
+            match s.node {
+                Some(id) if self.is_claimable(id, config.node, true) => {
+                    self.claims.insert(id, Claim::Section(path.clone()));
+                    self.claim(s, path, depth + 1)?;
+                }
+                _ => fresh.nested.push(s),
+            }
+        }
+
+        let is_main = match config.node {
+            Some(id) => id.document == 0,
+            None => true,
+        };
+
+        if is_main {
+            self.sections.push(fresh);
+        } else if let Some(p) = fresh.properties.first() {
+            return Err(ConfigError::IncludeChanged(config.get_location(), p.name.clone()));
+        } else if let Some(s) = fresh.nested.first() {
+            return Err(ConfigError::IncludeChanged(config.get_location(), s.name.clone()));
+        }
+
+        Ok(())
+    }
+
+    /// Checks that items of the included files are kept as is.
+    /// Included files are not saved, so changed or removed items would be lost
+    fn check_includes(&self) -> Result<()> {
+        for (document, item) in self.documents.iter().enumerate().skip(1) {
+            for (index, node) in item.nodes.iter().enumerate() {
+                let id = NodeId { origin: item.id, document, index };
+                let claim = self.claims.get(&id);
+
+                let (name, range, changed) = match &node.kind {
+                    Kind::Section { path, range, .. } => {
+                        let changed = match claim {
+                            Some(Claim::Section(expected)) => path != expected,
+                            _ => true,
+                        };
+                        (path, range, changed)
+                    }
+                    Kind::Property { name, name_range, value, .. } => {
+                        let changed = match claim {
+                            Some(Claim::Property(p)) => *name != p.name || *value != p.value,
+                            _ => true,
+                        };
+                        (name, name_range, changed)
+                    }
+                    _ => continue,
+                };
+
+                if changed {
+                    let location = node.location(&item.file, range.clone());
+                    return Err(ConfigError::IncludeChanged(location, name.to_owned()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks if node with `index` in the main document is inside section declared with `section` node
+    fn is_descendant(&self, index: usize, section: Option<NodeId>) -> bool {
+        let node = &self.documents[0].nodes[index];
+        let mut owner = match node.kind {
+            Kind::Section { .. } => Some(self.main_id(index)),
+            _ => node.owner,
+        };
+
+        loop {
+            if owner == section {
+                return true;
+            }
+
+            match owner {
+                Some(id) => owner = self.node(id).owner,
+                None => return false,
+            }
+        }
+    }
+
+    /// Returns position in the main document to insert new properties of the section
+    fn properties_position(&self, section: Option<NodeId>) -> Option<usize> {
+        let nodes = &self.documents[0].nodes;
+
+        let last = nodes.iter().enumerate().rev()
+            .find(|(_, node)| node.owner == section && matches!(node.kind, Kind::Property { .. }))
+            .map(|(index, _)| index);
+
+        match (last, section) {
+            (Some(index), _) => Some(index),
+            (None, Some(id)) => Some(id.index),
+            (None, None) => {
+                let first = nodes.iter()
+                    .position(|node| matches!(node.kind, Kind::Section { .. }))
+                    .unwrap_or(nodes.len());
+                first.checked_sub(1)
+            }
+        }
+    }
+
+    /// Returns position in the main document to insert new nested sections of the section
+    fn nested_position(&self, section: Option<NodeId>) -> Option<usize> {
+        let nodes = &self.documents[0].nodes;
+        let mut result = section.map(|id| id.index);
+        let start = result.map_or(0, |v| v + 1);
+
+        for (index, node) in nodes.iter().enumerate().skip(start) {
+            if matches!(node.kind, Kind::Blank) {
+                continue;
+            }
+            if ! self.is_descendant(index, section) {
+                break;
+            }
+            result = Some(index);
+        }
+
+        result
+    }
+
+    fn is_alive(&self, owner: Option<NodeId>) -> bool {
+        match owner {
+            Some(id) => self.claims.contains_key(&id),
+            None => true,
+        }
+    }
+
+    fn dump_node<W: Write>(&self, index: usize, dst: &mut W) -> io::Result<()> {
+        let node = &self.documents[0].nodes[index];
+        let id = self.main_id(index);
+
+        match (&node.kind, self.claims.get(&id)) {
+            (Kind::Blank, _) | (Kind::Include { .. }, _) if self.is_alive(node.owner) => {
+                dst.write_all(node.text.as_bytes())?;
+            }
+
+            (Kind::Section { path, range, .. }, Some(Claim::Section(expected))) => {
+                if path == expected {
+                    dst.write_all(node.text.as_bytes())?;
+                } else {
+                    write!(dst, "{}{}{}", &node.text[.. range.start], expected, &node.text[range.end ..])?;
+                }
+            }
+
+            (Kind::Property { name, name_range, value, value_range, style, .. }, Some(Claim::Property(p))) => {
+                if *name == p.name && *value == p.value {
+                    dst.write_all(node.text.as_bytes())?;
+                } else {
+                    /* trailing comment of the single-line value is kept in the node text */
+                    let value = match style {
+                        Style::Line | Style::Inline => format_value(&p.value, &p.style, None),
+                        _ => format_value(&p.value, &p.style, p.get_comment()),
+                    };
+
+                    write!(dst, "{}{}{}{}{}",
+                        &node.text[.. name_range.start],
+                        &p.name,
+                        &node.text[name_range.end .. value_range.start],
+                        &value,
+                        &node.text[value_range.end ..])?;
+                }
+            }
+
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn dump<W: Write>(&self, dst: &mut W) -> io::Result<()> {
+        let mut inserts: BTreeMap<(Option<usize>, usize, usize), &Fresh> = BTreeMap::new();
+        for (i, fresh) in self.sections.iter().enumerate() {
+            if ! fresh.properties.is_empty() {
+                let position = self.properties_position(fresh.node);
+                inserts.insert((position, 0, i), fresh);
+            }
+
+            if ! fresh.nested.is_empty() {
+                let position = self.nested_position(fresh.node);
+                inserts.insert((position, usize::MAX - fresh.depth, i), fresh);
+            }
+        }
+
+        let mut result = Vec::new();
+        let mut inserts = inserts.into_iter().peekable();
+        let count = self.documents[0].nodes.len();
+
+        for index in 0 ..= count {
+            let position = index.checked_sub(1);
+            while let Some(((p, order, _), fresh)) = inserts.peek() {
+                if *p != position {
+                    break;
+                }
+
+                if ! result.is_empty() && ! result.ends_with(b"\n") {
+                    result.push(b'\n');
+                }
+
+                if *order == 0 {
+                    fresh.dump_properties(&mut result)?;
+                } else {
+                    fresh.dump_nested(&mut result)?;
+                }
+
+                inserts.next();
+            }
+
+            if index < count {
+                self.dump_node(index, &mut result)?;
+            }
+        }
+
+        dst.write_all(&result)
+    }
+}
+
+
+/// Writes config with original formatting of the unchanged items.
+/// `documents` - main document and included documents.
+/// Returns error if items of the included documents are changed
+pub(crate) fn dump_config<W: Write>(documents: &[Document], config: &Config, dst: &mut W) -> Result<()> {
+    let mut merge = Merge {
+        documents,
+        claims: HashMap::new(),
+        sections: Vec::new(),
+    };
+
+    merge.claim(config, String::new(), 0)?;
+    merge.check_includes()?;
+    merge.dump(dst)?;
+    Ok(())
+}
@@ -0,0 +1,295 @@
+use std::collections::HashMap;
+
+use crate::config::{
+    Config,
+    ConfigError,
+    Location,
+    Property,
+    Result,
+    Value,
+};
+
+use crate::value::{
+    Style,
+    format_array,
+    format_map,
+};
+
+
+/// Property identifier: list of the nested section indexes and property index
+type Key = (Vec<usize>, usize);
+
+
+enum State {
+    Progress,
+    Done(String),
+}
+
+
+/// Returns nested section by the list of indexes
+fn section<'a>(root: &'a Config, path: &[usize]) -> &'a Config {
+    let mut last = root;
+    for &i in path {
+        last = &last.nested[i];
+    }
+    last
+}
+
+
+/// Returns position of the `}` symbol that closes reference started at the beginning of the `text`
+fn find_close(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut skip = 0;
+
+    while let Some(i) = text[skip ..].find(['{', '}']) {
+        let i = i + skip;
+        if text[i ..].starts_with('{') {
+            depth += 1;
+        } else {
+            if depth == 0 {
+                return Some(i);
+            }
+            depth -= 1;
+        }
+        skip = i + 1;
+    }
+
+    None
+}
+
+
+/// Expands `${...}` references in the property values
+struct Interpolate<'a> {
+    root: &'a Config,
+    env: Option<&'a HashMap<String, String>>,
+    states: HashMap<Key, State>,
+    /// Expanded values of the inline array or map items
+    items: HashMap<Key, Vec<String>>,
+}
+
+
+impl<'a> Interpolate<'a> {
+    /// Looks for property with `name` in the section and in the parent sections.
+    /// Name with `/` is an absolute path to the property
+    fn lookup(&self, name: &str, path: &[usize]) -> Option<Key> {
+        if let Some(skip) = name.rfind('/') {
+            let mut last = self.root;
+            let mut target = Vec::new();
+
+            for item in name[.. skip].split('/') {
+                let i = last.nested.iter().position(|s| s.name == item)?;
+                last = &last.nested[i];
+                target.push(i);
+            }
+
+            let i = last.properties.iter().position(|p| p.name == name[skip + 1 ..])?;
+            return Some((target, i));
+        }
+
+        for depth in (0 ..= path.len()).rev() {
+            let last = section(self.root, &path[.. depth]);
+            if let Some(i) = last.properties.iter().position(|p| p.name == name) {
+                return Some((path[.. depth].to_vec(), i));
+            }
+        }
+
+        None
+    }
+
+    fn get_env(&self, name: &str) -> Option<String> {
+        match self.env {
+            Some(env) => env.get(name).cloned(),
+            None => std::env::var(name).ok(),
+        }
+    }
+
+    fn expand(&mut self, text: &str, path: &[usize], location: &Location) -> Result<String> {
+        let mut result = String::with_capacity(text.len());
+        let mut skip = 0;
+
+        while let Some(i) = text[skip ..].find('$') {
+            let i = i + skip;
+            result.push_str(&text[skip .. i]);
+
+            let tail = &text[i + 1 ..];
+            if tail.starts_with('$') {
+                result.push('$');
+                skip = i + 2;
+                continue;
+            }
+
+            if ! tail.starts_with('{') {
+                result.push('$');
+                skip = i + 1;
+                continue;
+            }
+
+            let inner = &tail[1 ..];
+            let end = find_close(inner)
+                .ok_or_else(|| ConfigError::InvalidFormat(location.clone()))?;
+            let inner = &inner[.. end];
+            skip = i + 2 + end + 1;
+
+            let (name, default) = match inner.find(":-") {
+                Some(v) => (&inner[.. v], Some(&inner[v + 2 ..])),
+                None => (inner, None),
+            };
+
+            let value = match self.lookup(name, path) {
+                Some(key) => Some(self.resolve(key)?),
+                None => self.get_env(name),
+            };
+
+            match (value, default) {
+                (Some(value), Some(default)) if value.is_empty() => {
+                    result.push_str(&self.expand(default, path, location)?);
+                }
+                (Some(value), _) => result.push_str(&value),
+                (None, Some(default)) => {
+                    result.push_str(&self.expand(default, path, location)?);
+                }
+                (None, None) => {
+                    return Err(ConfigError::UndefinedReference(location.clone(), name.to_owned()));
+                }
+            }
+        }
+
+        result.push_str(&text[skip ..]);
+        Ok(result)
+    }
+
+    /// Expands property value. Items of the inline array or map are expanded separately
+    fn expand_property(&mut self, property: &Property, key: &Key) -> Result<String> {
+        let items = match &property.structured {
+            Value::Scalar => return self.expand(&property.value, &key.0, &property.get_value_location()),
+            Value::Array(items) | Value::Map(items) => items,
+        };
+
+        let mut values = Vec::with_capacity(items.len());
+        for item in items {
+            values.push(self.expand(&item.value, &key.0, &item.get_value_location())?);
+        }
+
+        let value = match &property.structured {
+            Value::Map(_) => format_map(items.iter().map(|p| p.name.as_str()).zip(values.iter().map(String::as_str))),
+            _ => format_array(values.iter().map(String::as_str)),
+        };
+
+        self.items.insert(key.clone(), values);
+        Ok(value)
+    }
+
+    fn resolve(&mut self, key: Key) -> Result<String> {
+        let property = &section(self.root, &key.0).properties[key.1];
+
+        match self.states.get(&key) {
+            Some(State::Done(value)) => return Ok(value.clone()),
+            Some(State::Progress) => {
+                return Err(ConfigError::ReferenceCycle(property.get_value_location(), property.name.clone()));
+            }
+            None => {}
+        }
+
+        let value = if let Style::Heredoc(_) = property.style {
+            property.value.clone()
+        } else {
+            self.states.insert(key.clone(), State::Progress);
+            match self.expand_property(property, &key) {
+                Ok(v) => v,
+                Err(e) => {
+                    /* keep value as is to report error once */
+                    self.states.insert(key, State::Done(property.value.clone()));
+                    return Err(e);
+                }
+            }
+        };
+
+        self.states.insert(key, State::Done(value.clone()));
+        Ok(value)
+    }
+
+    /// Resolves all properties in the section and nested sections.
+    /// If `errors` defined then collects errors and keeps values with errors as is
+    fn collect(&mut self, path: &mut Vec<usize>, errors: &mut Option<&mut Vec<ConfigError>>) -> Result<()> {
+        let last = section(self.root, path);
+
+        for i in 0 .. last.properties.len() {
+            if let Err(e) = self.resolve((path.clone(), i)) {
+                match errors {
+                    Some(errors) => errors.push(e),
+                    None => return Err(e),
+                }
+            }
+        }
+
+        for i in 0 .. last.nested.len() {
+            path.push(i);
+            self.collect(path, errors)?;
+            path.pop();
+        }
+
+        Ok(())
+    }
+}
+
+
+fn apply(
+    config: &mut Config,
+    path: &mut Vec<usize>,
+    states: &mut HashMap<Key, State>,
+    values: &mut HashMap<Key, Vec<String>>,
+) {
+    for (i, p) in config.properties.iter_mut().enumerate() {
+        let key = (path.clone(), i);
+
+        if let Some(State::Done(value)) = states.remove(&key) {
+            p.value = value;
+        }
+
+        if let Some(list) = values.remove(&key) {
+            if let Value::Array(items) | Value::Map(items) = &mut p.structured {
+                for (item, value) in items.iter_mut().zip(list) {
+                    item.value = value;
+                }
+            }
+        }
+    }
+
+    for (i, s) in config.nested.iter_mut().enumerate() {
+        path.push(i);
+        apply(s, path, states, values);
+        path.pop();
+    }
+}
+
+
+/// Expands references in the property values:
+///
+/// - `$$` - `$` symbol
+/// - `${name}` - value of the property in the current section or in the parent sections,
+///   otherwise value of the environment variable
+/// - `${section/name}` - value of the property in the section with absolute path
+/// - `${name:-default}` - default value if reference is not defined or empty
+///
+/// `env` - variables instead of the process environment.
+/// `errors` - collects errors instead of returning first one
+pub(crate) fn interpolate(
+    root: &mut Config,
+    env: Option<&HashMap<String, String>>,
+    mut errors: Option<&mut Vec<ConfigError>>,
+) -> Result<()> {
+    let mut interpolate = Interpolate {
+        root,
+        env,
+        states: HashMap::new(),
+        items: HashMap::new(),
+    };
+
+    interpolate.collect(&mut Vec::new(), &mut errors)?;
+
+    let mut states = interpolate.states;
+    let mut values = interpolate.items;
+    apply(root, &mut Vec::new(), &mut states, &mut values);
+
+    Ok(())
+}
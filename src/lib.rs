@@ -5,7 +5,68 @@ mod config;
 pub use crate::config::{
     Config,
     ConfigError,
+    Location,
+    Property,
+    Value,
+    StrictBool,
+    FromProperty,
+    ToProperty,
+    ScalarProperty,
+    FromConfig,
 };
 
+#[cfg(feature = "derive")]
+pub use config_derive::{
+    FromConfig,
+    HasSchema,
+};
+
+mod value;
+
+mod number;
+pub use crate::number::Hex;
+
+mod units;
+pub use crate::units::ByteSize;
+
+mod net;
+pub use crate::net::{
+    Cidr,
+    StreamUrl,
+};
+
+mod path;
+pub use crate::path::PathCheck;
+
+mod document;
+pub use crate::document::Document;
+
+mod loader;
+pub use crate::loader::Loader;
+
+mod interpolate;
+
+mod query;
+pub use crate::query::{
+    QueryItem,
+    QueryItemMut,
+};
+
+mod diagnostic;
+pub use crate::diagnostic::Diagnostic;
+
+#[cfg(feature = "serde")]
+mod de;
+#[cfg(feature = "serde")]
+pub use crate::de::from_config;
+
+#[cfg(feature = "serde")]
+mod ser;
+#[cfg(feature = "serde")]
+pub use crate::ser::to_config;
+
 mod schema;
-pub use crate::schema::Schema;
+pub use crate::schema::{
+    Schema,
+    HasSchema,
+};
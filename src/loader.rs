@@ -0,0 +1,447 @@
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    fs::File,
+    io::Read,
+    sync::Arc,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use crate::config::{
+    Config,
+    ConfigError,
+    Location,
+    Property,
+    Result,
+    Value,
+};
+
+use crate::document::{
+    Document,
+    Kind,
+    Node,
+    NodeId,
+};
+
+use crate::value::{
+    Inline,
+    Item,
+};
+
+use crate::interpolate::interpolate;
+
+
+/// Returns nested section by the list of indexes
+fn section_mut<'a>(root: &'a mut Config, path: &[usize]) -> &'a mut Config {
+    let mut last = root;
+    for &i in path {
+        last = &mut last.nested[i];
+    }
+    last
+}
+
+
+/// Returns list of the nested section indexes for the parent section
+/// and position of the section name in the `path`.
+/// Parent section should be the last declared section on each level
+fn find_parent(root: &Config, path: &str) -> Option<(Vec<usize>, usize)> {
+    let mut skip = 0;
+    let mut target = Vec::new();
+    let mut last = root;
+
+    loop {
+        let next = path[skip ..].find('/').map_or(0, |v| v + skip);
+        if next == 0 { break }
+        let item = &path[skip .. next];
+        skip = next + 1;
+
+        let i = last.nested.len().checked_sub(1)?;
+        last = &last.nested[i];
+
+        if last.name != item {
+            return None;
+        }
+
+        target.push(i);
+    }
+
+    Some((target, skip))
+}
+
+
+/// Returns items of the inline array or map as properties.
+/// Array items have same `name` as the array property
+fn build_items(node: &Node, file: &Option<Arc<Path>>, name: &str, items: &[Item]) -> Vec<Property> {
+    items.iter().map(|item| {
+        let name_location = node.location(file, item.key_range.clone());
+        let value_location = node.location(file, item.value_range.clone());
+
+        let mut property = Property::new(
+            item.key.clone().unwrap_or_else(|| name.to_owned()),
+            item.value.clone());
+        property.line = name_location.get_line();
+        property.column = name_location.get_column();
+        property.file = file.clone();
+        property.name_span = name_location.get_span();
+        property.value_column = value_location.get_column();
+        property.value_span = value_location.get_span();
+        property
+    }).collect()
+}
+
+
+/// Checks if path contains glob special symbols
+#[inline]
+fn is_pattern(path: &str) -> bool {
+    path.contains(['*', '?', '['])
+}
+
+
+/// Config loader
+///
+/// Builds config tree from the document, resolves included files
+/// and expands references in the property values.
+/// References to the environment variables are resolved with process environment
+/// or with variables defined by `Loader::set_env`.
+///
+/// ```
+/// use config::Loader;
+///
+/// let mut loader = Loader::new();
+/// loader.set_env("HOST_IP", "127.0.0.1");
+/// let config = loader.parse("output = udp://${HOST_IP}:10000".as_bytes()).unwrap();
+/// assert_eq!(config.get("output"), Some("udp://127.0.0.1:10000"));
+/// ```
+#[derive(Default)]
+pub struct Loader {
+    /// Main document and included documents
+    documents: Vec<Document>,
+    /// Canonical paths of the files in the include chain
+    stack: Vec<PathBuf>,
+    /// Variables instead of the process environment
+    env: Option<HashMap<String, String>>,
+    /// Collected errors in the recovery mode
+    errors: Option<Vec<ConfigError>>,
+}
+
+
+/// Stores expanded values in the document nodes to detect changes on save
+fn update_documents(config: &Config, documents: &mut [Document]) {
+    for p in &config.properties {
+        if let Some(id) = p.node {
+            if let Kind::Property { value, .. } = &mut documents[id.document].nodes[id.index].kind {
+                value.clone_from(&p.value);
+            }
+        }
+    }
+
+    for s in &config.nested {
+        update_documents(s, documents);
+    }
+}
+
+
+/// Sorts errors by location: file, line, and column.
+/// Errors without location are placed at the end
+fn sort_errors(errors: &mut [ConfigError]) {
+    errors.sort_by(|a, b| match (a.get_location(), b.get_location()) {
+        (Some(a), Some(b)) => {
+            let a = (a.get_file(), a.get_line(), a.get_column());
+            a.cmp(&(b.get_file(), b.get_line(), b.get_column()))
+        }
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    });
+}
+
+
+impl Loader {
+    /// Creates new loader
+    #[inline]
+    pub fn new() -> Self { Loader::default() }
+
+    /// Defines variable for references in the property values.
+    /// If any variable is defined then process environment is not used
+    pub fn set_env<S, T>(&mut self, name: S, value: T)
+    where
+        S: Into<String>,
+        T: ToString,
+    {
+        self.env.get_or_insert_with(HashMap::new)
+            .insert(name.into(), value.to_string());
+    }
+
+    /// Returns error or keeps it in the recovery mode
+    fn report(&mut self, error: ConfigError) -> Result<()> {
+        match &mut self.errors {
+            Some(errors) => {
+                errors.push(error);
+                Ok(())
+            }
+            None => Err(error),
+        }
+    }
+
+    fn build_config(&mut self, document: Document) -> Result<Config> {
+        if let Some(file) = &document.file {
+            match file.canonicalize() {
+                Ok(v) => self.stack.push(v),
+                Err(e) => self.report(e.into())?,
+            }
+        }
+
+        let mut root = Config::new("");
+        root.file = document.file.clone();
+        self.documents.push(document);
+        self.build(&mut root, 0, &[])?;
+
+        interpolate(&mut root, self.env.as_ref(), self.errors.as_mut())?;
+        update_documents(&root, &mut self.documents);
+
+        root.documents = std::mem::take(&mut self.documents);
+        Ok(root)
+    }
+
+    /// Builds config from the document
+    #[inline]
+    pub fn load(mut self, document: Document) -> Result<Config> {
+        self.build_config(document)
+    }
+
+    /// Parses config
+    #[inline]
+    pub fn parse<R: Read>(self, src: R) -> Result<Config> {
+        let document = Document::parse(src)?;
+        self.load(document)
+    }
+
+    /// Opens config file
+    #[inline]
+    pub fn open<P: AsRef<Path>>(self, path: P) -> Result<Config> {
+        let document = Document::open(path)?;
+        self.load(document)
+    }
+
+    /// Builds config from the document in the recovery mode.
+    /// Skips items with errors and returns partial config with all found errors sorted by location
+    #[inline]
+    pub fn load_recover(self, document: Document) -> (Config, Vec<ConfigError>) {
+        self.recover(document, Vec::new())
+    }
+
+    fn recover(mut self, document: Document, errors: Vec<ConfigError>) -> (Config, Vec<ConfigError>) {
+        self.errors = Some(errors);
+        let config = self.build_config(document);
+        let mut errors = self.errors.take().unwrap_or_default();
+
+        let config = match config {
+            Ok(config) => config,
+            Err(e) => {
+                errors.push(e);
+                Config::new("")
+            }
+        };
+
+        sort_errors(&mut errors);
+        (config, errors)
+    }
+
+    /// Parses config in the recovery mode.
+    /// Skips lines with errors and returns partial config with all found errors sorted by location
+    pub fn parse_recover<R: Read>(self, src: R) -> (Config, Vec<ConfigError>) {
+        let mut errors = Vec::new();
+        let document = Document::read(src, None, &mut errors);
+        self.recover(document, errors)
+    }
+
+    /// Opens config file in the recovery mode.
+    /// Skips lines with errors and returns partial config with all found errors sorted by location
+    pub fn open_recover<P: AsRef<Path>>(self, path: P) -> (Config, Vec<ConfigError>) {
+        let path = path.as_ref();
+        let mut errors = Vec::new();
+        let document = match File::open(path) {
+            Ok(v) => Document::read(v, Some(Arc::from(path)), &mut errors),
+            Err(e) => return (Config::new(""), vec![e.into()]),
+        };
+        self.recover(document, errors)
+    }
+
+    /// Resolves paths to the included files
+    fn resolve(&self, location: &Location, pattern: &str) -> Result<Vec<PathBuf>> {
+        let base = location.get_file().and_then(Path::parent).unwrap_or_else(|| Path::new(""));
+        let path = base.join(pattern);
+
+        let invalid = || ConfigError::InvalidInclude(location.clone(), pattern.to_owned());
+
+        if ! is_pattern(pattern) {
+            if ! path.is_file() {
+                return Err(invalid());
+            }
+            return Ok(vec![path]);
+        }
+
+        let path = path.to_str().ok_or_else(invalid)?;
+        let mut result = Vec::new();
+        for item in glob::glob(path).map_err(|_| invalid())? {
+            let item = item.map_err(|_| invalid())?;
+            if item.is_file() {
+                result.push(item);
+            }
+        }
+
+        result.sort();
+        Ok(result)
+    }
+
+    /// Opens included file. In the recovery mode syntax errors are collected
+    fn open_include(&mut self, path: &Path) -> Result<Option<Document>> {
+        let errors = match &mut self.errors {
+            Some(v) => v,
+            None => return Ok(Some(Document::open(path)?)),
+        };
+
+        match File::open(path) {
+            Ok(v) => Ok(Some(Document::read(v, Some(Arc::from(path)), errors))),
+            Err(e) => {
+                errors.push(e.into());
+                Ok(None)
+            }
+        }
+    }
+
+    /// Appends items from the included files into the `section`
+    fn include(&mut self, root: &mut Config, location: &Location, pattern: &str, section: &[usize]) -> Result<()> {
+        let list = match self.resolve(location, pattern) {
+            Ok(v) => v,
+            Err(e) => return self.report(e),
+        };
+
+        for path in list {
+            let canonical = match path.canonicalize() {
+                Ok(v) => v,
+                Err(e) => {
+                    self.report(e.into())?;
+                    continue;
+                }
+            };
+
+            if self.stack.contains(&canonical) {
+                self.report(ConfigError::IncludeCycle(location.clone(), path.display().to_string()))?;
+                continue;
+            }
+
+            let document = match self.open_include(&path)? {
+                Some(v) => v,
+                None => continue,
+            };
+
+            self.documents.push(document);
+            self.stack.push(canonical);
+            self.build(root, self.documents.len() - 1, section)?;
+            self.stack.pop();
+        }
+
+        Ok(())
+    }
+
+    /// Appends items from the document into the config.
+    /// `section` - list of the nested section indexes to append properties
+    /// before first section declaration in the document.
+    /// In the recovery mode items of the section with invalid declaration
+    /// are skipped and kept in the document as is
+    fn build(&mut self, root: &mut Config, document: usize, section: &[usize]) -> Result<()> {
+        let file = self.documents[document].file.clone();
+        let origin = self.documents[document].id;
+        let mut current = section.to_vec();
+        let mut skip_section = false;
+
+        for index in 0 .. self.documents[document].nodes.len() {
+            let id = NodeId { origin, document, index };
+            let node = &mut self.documents[document].nodes[index];
+
+            match &node.kind {
+                Kind::Blank => {
+                    node.owner = section_mut(root, &current).node;
+                }
+
+                Kind::Section { path, range, comment } => {
+                    let location = node.location(&file, range.clone());
+                    let (mut target, skip) = match find_parent(root, path) {
+                        Some(v) => v,
+                        None => {
+                            let error = ConfigError::InvalidKey(location, path.to_owned());
+                            node.owner = section_mut(root, &current).node;
+                            node.kind = Kind::Blank;
+                            skip_section = true;
+                            self.report(error)?;
+                            continue;
+                        }
+                    };
+
+                    let last = section_mut(root, &target);
+                    node.owner = last.node;
+
+                    let mut nested = Config::new(&path[skip ..]);
+                    nested.line = node.line;
+                    nested.column = location.get_column();
+                    nested.span = location.get_span();
+                    nested.file = file.clone();
+                    nested.node = Some(id);
+                    nested.comment = comment.clone();
+
+                    target.push(last.nested.len());
+                    last.nested.push(nested);
+                    current = target;
+                    skip_section = false;
+                }
+
+                _ if skip_section => {
+                    node.owner = section_mut(root, &current).node;
+                    node.kind = Kind::Blank;
+                }
+
+                Kind::Property { name, name_range, value, value_range, style, comment, inline } => {
+                    let last = section_mut(root, &current);
+                    node.owner = last.node;
+
+                    let name_location = node.location(&file, name_range.clone());
+                    let value_location = node.location(&file, value_range.clone());
+
+                    let structured = match inline {
+                        Some(Inline::Array(items)) => Value::Array(build_items(node, &file, name, items)),
+                        Some(Inline::Map(items)) => Value::Map(build_items(node, &file, name, items)),
+                        None => Value::Scalar,
+                    };
+
+                    last.properties.push(Property {
+                        line: node.line,
+                        column: name_location.get_column(),
+                        file: file.clone(),
+                        node: Some(id),
+                        name: name.clone(),
+                        name_span: name_location.get_span(),
+                        value: value.clone(),
+                        value_column: value_location.get_column(),
+                        value_span: value_location.get_span(),
+                        style: style.clone(),
+                        comment: comment.clone(),
+                        structured,
+                    });
+                }
+
+                Kind::Include { pattern, range } => {
+                    let location = node.location(&file, range.clone());
+                    let pattern = pattern.clone();
+                    node.owner = section_mut(root, &current).node;
+                    self.include(root, &location, &pattern, &current)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,316 @@
+use std::{
+    fmt,
+    net::{
+        IpAddr,
+        Ipv4Addr,
+        Ipv6Addr,
+        SocketAddr,
+        SocketAddrV4,
+        SocketAddrV6,
+    },
+};
+
+use crate::config::{
+    ConfigError,
+    FromProperty,
+    Property,
+    Result,
+    ScalarProperty,
+    ToProperty,
+};
+
+
+macro_rules! impl_get_parse {
+    ( $( $t:tt ),* ) => {
+        $( impl<'a> FromProperty<'a> for $t {
+            #[inline]
+            fn from_property(p: &'a Property) -> Result<$t> {
+                p.value.parse::<$t>()
+                    .map_err(|_| ConfigError::InvalidProperty(p.get_value_location(), p.name.to_owned()))
+            }
+        } )*
+    };
+}
+
+
+impl_get_parse!(IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6);
+
+
+macro_rules! impl_set_display {
+    ( $( $t:tt ),* ) => {
+        $( impl ToProperty for $t {
+            #[inline]
+            fn to_property(&self, p: &mut Property) { p.set_scalar(self.to_string()) }
+        }
+
+        impl ScalarProperty for $t {} )*
+    };
+}
+
+
+impl_set_display!(IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6, Cidr, StreamUrl);
+
+
+/// IP network in the CIDR notation. Example: `10.0.0.0/8`, `fd00::/8`.
+/// Address without prefix is a network with single host
+///
+/// ```
+/// use config::{Config, Cidr};
+///
+/// let config = Config::parse("allow = 192.168.0.0/16".as_bytes()).unwrap();
+/// let allow: Cidr = config.get("allow").unwrap();
+/// assert!(allow.contains(&"192.168.1.10".parse().unwrap()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cidr {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+
+impl Cidr {
+    /// Creates network. Returns `None` if prefix is longer than address
+    pub fn new(addr: IpAddr, prefix: u8) -> Option<Cidr> {
+        let bits = if addr.is_ipv4() { 32 } else { 128 };
+        if prefix > bits {
+            None
+        } else {
+            Some(Cidr { addr, prefix })
+        }
+    }
+
+    /// Parses network in the CIDR notation
+    pub fn parse(value: &str) -> Option<Cidr> {
+        match value.split_once('/') {
+            Some((addr, prefix)) => {
+                if ! prefix.bytes().all(|c| c.is_ascii_digit()) {
+                    return None;
+                }
+                Cidr::new(addr.parse().ok()?, prefix.parse().ok()?)
+            }
+            None => {
+                let addr: IpAddr = value.parse().ok()?;
+                let prefix = if addr.is_ipv4() { 32 } else { 128 };
+                Cidr::new(addr, prefix)
+            }
+        }
+    }
+
+    /// Returns network address as defined in the config
+    #[inline]
+    pub fn get_addr(&self) -> IpAddr { self.addr }
+
+    /// Returns prefix length
+    #[inline]
+    pub fn get_prefix(&self) -> u8 { self.prefix }
+
+    /// Checks if address belongs to the network
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        match (self.addr, addr) {
+            (IpAddr::V4(net), IpAddr::V4(addr)) => {
+                let mask = u32::MAX.checked_shl(32 - u32::from(self.prefix)).unwrap_or(0);
+                u32::from(net) & mask == u32::from(*addr) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(addr)) => {
+                let mask = u128::MAX.checked_shl(128 - u32::from(self.prefix)).unwrap_or(0);
+                u128::from(net) & mask == u128::from(*addr) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+
+/// Parsed stream address: `scheme://[interface@]host[:port][/path][?options]`
+///
+/// - `interface` - network interface name or address to receive or send stream
+/// - `host` - host name or IP address. IPv6 address should be wrapped into `[]`
+/// - `options` - list of `name=value` or `name` separated with `&`
+///
+/// ```
+/// use config::{Config, StreamUrl};
+///
+/// let config = Config::parse("output = udp://eth0@239.255.1.1:10000?ttl=4&sync".as_bytes()).unwrap();
+/// let output: StreamUrl = config.get("output").unwrap();
+/// assert_eq!(output.get_scheme(), "udp");
+/// assert_eq!(output.get_interface(), Some("eth0"));
+/// assert_eq!(output.get_host(), "239.255.1.1");
+/// assert_eq!(output.get_port(), Some(10000));
+/// assert_eq!(output.get_option("ttl"), Some("4"));
+/// assert_eq!(output.get_option("sync"), Some(""));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamUrl {
+    scheme: String,
+    interface: Option<String>,
+    host: String,
+    port: Option<u16>,
+    path: String,
+    options: Vec<(String, String)>,
+}
+
+
+impl StreamUrl {
+    /// Parses stream address
+    pub fn parse(value: &str) -> Option<StreamUrl> {
+        let (scheme, tail) = value.split_once("://")?;
+        if scheme.is_empty() || ! scheme.chars().all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.') {
+            return None;
+        }
+
+        let (tail, query) = match tail.split_once('?') {
+            Some((tail, query)) => (tail, Some(query)),
+            None => (tail, None),
+        };
+
+        let (authority, path) = match tail.find('/') {
+            Some(i) => tail.split_at(i),
+            None => (tail, ""),
+        };
+
+        let (interface, authority) = match authority.rsplit_once('@') {
+            Some((interface, authority)) if ! interface.is_empty() => (Some(interface.to_owned()), authority),
+            Some(_) => return None,
+            None => (None, authority),
+        };
+
+        let (host, port) = if let Some(tail) = authority.strip_prefix('[') {
+            let (host, tail) = tail.split_once(']')?;
+            host.parse::<Ipv6Addr>().ok()?;
+            (host, tail)
+        } else {
+            match authority.find(':') {
+                Some(i) => authority.split_at(i),
+                None => (authority, ""),
+            }
+        };
+
+        if host.is_empty() {
+            return None;
+        }
+
+        let port = match port {
+            "" => None,
+            v => Some(v.strip_prefix(':')?.parse::<u16>().ok()?),
+        };
+
+        let mut options = Vec::new();
+        for item in query.unwrap_or("").split('&').filter(|v| ! v.is_empty()) {
+            let (name, value) = item.split_once('=').unwrap_or((item, ""));
+            options.push((name.to_owned(), value.to_owned()));
+        }
+
+        Some(StreamUrl {
+            scheme: scheme.to_ascii_lowercase(),
+            interface,
+            host: host.to_owned(),
+            port,
+            path: path.to_owned(),
+            options,
+        })
+    }
+
+    /// Returns scheme in lower case
+    #[inline]
+    pub fn get_scheme(&self) -> &str { self.scheme.as_str() }
+
+    /// Returns interface defined before `@` symbol
+    #[inline]
+    pub fn get_interface(&self) -> Option<&str> { self.interface.as_deref() }
+
+    /// Returns host name or IP address. IPv6 address is returned without `[]`
+    #[inline]
+    pub fn get_host(&self) -> &str { self.host.as_str() }
+
+    /// Returns host IP address or `None` if host is a name
+    #[inline]
+    pub fn get_addr(&self) -> Option<IpAddr> { self.host.parse().ok() }
+
+    #[inline]
+    pub fn get_port(&self) -> Option<u16> { self.port }
+
+    /// Returns path with leading `/` or empty string
+    #[inline]
+    pub fn get_path(&self) -> &str { self.path.as_str() }
+
+    /// Returns value of the first option with `name`. Option without value returns empty string
+    pub fn get_option(&self, name: &str) -> Option<&str> {
+        self.options.iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Returns all options in order of definition
+    #[inline]
+    pub fn get_options(&self) -> &[(String, String)] { &self.options }
+}
+
+
+impl fmt::Display for StreamUrl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}://", &self.scheme)?;
+
+        if let Some(interface) = &self.interface {
+            write!(f, "{}@", interface)?;
+        }
+
+        if self.host.contains(':') {
+            write!(f, "[{}]", &self.host)?;
+        } else {
+            f.write_str(&self.host)?;
+        }
+
+        if let Some(port) = self.port {
+            write!(f, ":{}", port)?;
+        }
+
+        f.write_str(&self.path)?;
+
+        for (i, (name, value)) in self.options.iter().enumerate() {
+            f.write_str(if i == 0 { "?" } else { "&" })?;
+            f.write_str(name)?;
+            if ! value.is_empty() {
+                write!(f, "={}", value)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+
+impl<'a> FromProperty<'a> for Cidr {
+    fn from_property(p: &'a Property) -> Result<Cidr> {
+        Cidr::parse(&p.value)
+            .ok_or_else(|| ConfigError::InvalidProperty(p.get_value_location(), p.name.to_owned()))
+    }
+}
+
+
+impl<'a> FromProperty<'a> for StreamUrl {
+    fn from_property(p: &'a Property) -> Result<StreamUrl> {
+        StreamUrl::parse(&p.value)
+            .ok_or_else(|| ConfigError::InvalidProperty(p.get_value_location(), p.name.to_owned()))
+    }
+}
+
+
+/// Returns IP address from the address, socket address, or stream address
+pub(crate) fn parse_ip(value: &str) -> Option<IpAddr> {
+    if let Ok(addr) = value.parse::<IpAddr>() {
+        return Some(addr);
+    }
+
+    if let Ok(addr) = value.parse::<SocketAddr>() {
+        return Some(addr.ip());
+    }
+
+    StreamUrl::parse(value)?.get_addr()
+}
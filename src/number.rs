@@ -0,0 +1,108 @@
+use std::convert::TryFrom;
+
+use crate::config::{
+    self,
+    FromProperty,
+    Property,
+    ScalarProperty,
+    ToProperty,
+};
+
+
+/// Number parsing error
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum NumberError {
+    /// Value is not a number
+    Syntax,
+    /// Number is out of range of the type
+    Overflow,
+}
+
+
+/// Parses integer literal:
+///
+/// - optional sign: `+` or `-`
+/// - optional radix prefix: `0x` - hexadecimal, `0o` - octal, `0b` - binary.
+///   Prefix is case-insensitive
+/// - digits with optional `_` separators between them. Example: `1_000_000`
+pub(crate) fn parse_number<T: TryFrom<i128>>(value: &str) -> Result<T, NumberError> {
+    let (negative, value) = match value.as_bytes().first() {
+        Some(b'-') => (true, &value[1 ..]),
+        Some(b'+') => (false, &value[1 ..]),
+        _ => (false, value),
+    };
+
+    let prefix = value.get(.. 2).map(str::to_ascii_lowercase);
+    let (radix, digits) = match prefix.as_deref() {
+        Some("0x") => (16, &value[2 ..]),
+        Some("0o") => (8, &value[2 ..]),
+        Some("0b") => (2, &value[2 ..]),
+        _ => (10, value),
+    };
+
+    if digits.is_empty() || digits.starts_with('_') || digits.ends_with('_') {
+        return Err(NumberError::Syntax);
+    }
+
+    let mut magnitude = 0u128;
+    let mut overflow = false;
+
+    for c in digits.chars().filter(|&c| c != '_') {
+        let digit = c.to_digit(radix).ok_or(NumberError::Syntax)?;
+        match magnitude.checked_mul(u128::from(radix)).and_then(|v| v.checked_add(u128::from(digit))) {
+            Some(v) => magnitude = v,
+            /* check syntax of the remaining digits */
+            None => overflow = true,
+        }
+    }
+
+    if overflow {
+        return Err(NumberError::Overflow);
+    }
+
+    let value = i128::try_from(magnitude).map_err(|_| NumberError::Overflow)?;
+    let value = if negative { -value } else { value };
+    T::try_from(value).map_err(|_| NumberError::Overflow)
+}
+
+
+/// Integer in the hexadecimal form. Example: `0x1FFF`.
+/// Formatting hint for `Config::set` to write PIDs and other identifiers.
+/// On reading accepts same integer literals as the inner type
+///
+/// ```
+/// use config::{Config, Hex};
+///
+/// let mut config = Config::new("");
+/// config.set("pid", Hex(256u16));
+/// assert_eq!(config.get("pid"), Some("0x100"));
+/// assert_eq!(config.get("pid"), Some(256u16));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Hex<T>(pub T);
+
+
+impl<'a, T: FromProperty<'a>> FromProperty<'a> for Hex<T> {
+    #[inline]
+    fn from_property(p: &'a Property) -> config::Result<Hex<T>> {
+        T::from_property(p).map(Hex)
+    }
+}
+
+
+macro_rules! impl_set_hex {
+    ( $( $t:tt ),* ) => {
+        $( impl ToProperty for Hex<$t> {
+            fn to_property(&self, p: &mut Property) {
+                let value = self.0 as i128;
+                let sign = if value < 0 { "-" } else { "" };
+                p.set_scalar(format!("{}0x{:X}", sign, value.unsigned_abs()))
+            }
+        }
+
+        impl ScalarProperty for Hex<$t> {} )*
+    };
+}
+
+
+impl_set_hex!(u8, i8, u16, i16, u32, i32, u64, i64, usize, isize);
@@ -0,0 +1,119 @@
+use std::{
+    env,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use crate::config::{
+    ConfigError,
+    FromProperty,
+    Property,
+    Result,
+    ScalarProperty,
+    ToProperty,
+};
+
+
+/// Existence check for the path property. See `Schema::set_path`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathCheck {
+    /// Path is not checked
+    Any,
+    /// Path should exist
+    Exists,
+    /// Path should be a regular file
+    File,
+    /// Path should be a directory
+    Dir,
+}
+
+
+impl PathCheck {
+    /// Checks resolved path
+    pub(crate) fn check(self, path: &Path) -> bool {
+        match self {
+            PathCheck::Any => true,
+            PathCheck::Exists => path.exists(),
+            PathCheck::File => path.is_file(),
+            PathCheck::Dir => path.is_dir(),
+        }
+    }
+}
+
+
+/// Returns home directory of the current user
+fn home_dir() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .filter(|v| ! v.is_empty())
+        .map(PathBuf::from)
+}
+
+
+/// Resolves path defined in the `file`:
+///
+/// - `~` and `~/path` - relative to the home directory
+/// - absolute path is returned as is
+/// - relative path is resolved against directory of the `file`,
+///   or against current directory if config is not parsed from the file
+pub(crate) fn resolve_path(file: Option<&Path>, value: &str) -> Option<PathBuf> {
+    if value.is_empty() {
+        return None;
+    }
+
+    if value == "~" {
+        return home_dir();
+    }
+
+    if let Some(tail) = value.strip_prefix("~/") {
+        return Some(home_dir()?.join(tail));
+    }
+
+    let path = Path::new(value);
+    if path.is_absolute() {
+        return Some(path.to_owned());
+    }
+
+    let base = file.and_then(Path::parent).unwrap_or_else(|| Path::new(""));
+    Some(base.join(path))
+}
+
+
+/// Path relative to the file where property is defined.
+/// Property from the included file is resolved against the included file.
+/// Leading `~` is expanded to the home directory
+///
+/// ```
+/// use std::path::PathBuf;
+/// use config::Config;
+///
+/// let config = Config::parse("xmltv = /opt/epg.xml".as_bytes()).unwrap();
+/// assert_eq!(config.get("xmltv"), Some(PathBuf::from("/opt/epg.xml")));
+/// ```
+impl<'a> FromProperty<'a> for PathBuf {
+    fn from_property(p: &'a Property) -> Result<PathBuf> {
+        resolve_path(p.get_file(), &p.value)
+            .ok_or_else(|| ConfigError::InvalidProperty(p.get_value_location(), p.name.to_owned()))
+    }
+}
+
+
+/// Path is written as is. Relative path is resolved against the file on reading
+impl ToProperty for Path {
+    #[inline]
+    fn to_property(&self, p: &mut Property) { p.set_scalar(self.to_string_lossy().into_owned()) }
+}
+
+
+impl ToProperty for PathBuf {
+    #[inline]
+    fn to_property(&self, p: &mut Property) { self.as_path().to_property(p) }
+}
+
+
+impl ScalarProperty for Path {}
+
+
+impl ScalarProperty for PathBuf {}
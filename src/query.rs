@@ -0,0 +1,243 @@
+use crate::config::{
+    Config,
+    ConfigError,
+    Property,
+    Result,
+};
+
+
+/// Section filter in the query segment
+enum Filter {
+    /// `name` or `name[*]` - all sections
+    All,
+    /// `name[0]` - section with index in the list of sections with same name
+    Index(usize),
+    /// `name[key=value]` - sections with property
+    Property(String, String),
+}
+
+
+/// Query segment: section or property name with optional filter.
+/// Name `None` is a wildcard
+struct Segment {
+    name: Option<String>,
+    filter: Filter,
+}
+
+
+impl Segment {
+    #[inline]
+    fn is_name(&self, name: &str) -> bool {
+        match &self.name {
+            Some(v) => v == name,
+            None => true,
+        }
+    }
+
+    /// Checks if section matches segment. `index` - position in the list of sections with same name
+    fn is_match(&self, config: &Config, index: usize) -> bool {
+        match &self.filter {
+            Filter::All => true,
+            Filter::Index(v) => *v == index,
+            Filter::Property(key, value) => config.properties.iter()
+                .any(|p| &p.name == key && &p.value == value),
+        }
+    }
+}
+
+
+/// Parses query path
+fn parse(path: &str) -> Result<Vec<Segment>> {
+    let invalid = || ConfigError::InvalidQuery(path.to_owned());
+
+    let mut result = Vec::new();
+    let mut skip = 0;
+
+    loop {
+        let tail = &path[skip ..];
+        let end = tail.find(['/', '[']).unwrap_or(tail.len());
+        let name = &tail[.. end];
+        if name.is_empty() {
+            return Err(invalid());
+        }
+        skip += end;
+
+        let mut filter = Filter::All;
+        if path[skip ..].starts_with('[') {
+            let close = path[skip ..].find(']').ok_or_else(invalid)?;
+            let selector = path[skip + 1 .. skip + close].trim();
+            skip += close + 1;
+
+            filter = if selector == "*" {
+                Filter::All
+            } else if let Some(v) = selector.find('=') {
+                let key = selector[.. v].trim();
+                if key.is_empty() {
+                    return Err(invalid());
+                }
+                Filter::Property(key.to_owned(), selector[v + 1 ..].trim().to_owned())
+            } else {
+                Filter::Index(selector.parse().map_err(|_| invalid())?)
+            };
+        }
+
+        result.push(Segment {
+            name: if name == "*" { None } else { Some(name.to_owned()) },
+            filter,
+        });
+
+        if skip == path.len() {
+            break;
+        }
+
+        if ! path[skip ..].starts_with('/') {
+            return Err(invalid());
+        }
+        skip += 1;
+    }
+
+    Ok(result)
+}
+
+
+/// Item selected with `Config::query`
+pub enum QueryItem<'a> {
+    Section(&'a Config),
+    Property(&'a Property),
+}
+
+
+impl<'a> QueryItem<'a> {
+    /// Returns section or `None` if item is a property
+    #[inline]
+    pub fn as_section(&self) -> Option<&'a Config> {
+        match self {
+            QueryItem::Section(v) => Some(v),
+            QueryItem::Property(_) => None,
+        }
+    }
+
+    /// Returns property or `None` if item is a section
+    #[inline]
+    pub fn as_property(&self) -> Option<&'a Property> {
+        match self {
+            QueryItem::Section(_) => None,
+            QueryItem::Property(v) => Some(v),
+        }
+    }
+}
+
+
+/// Item selected with `Config::query_mut`
+pub enum QueryItemMut<'a> {
+    Section(&'a mut Config),
+    Property(&'a mut Property),
+}
+
+
+impl<'a> QueryItemMut<'a> {
+    /// Returns section or `None` if item is a property
+    #[inline]
+    pub fn into_section(self) -> Option<&'a mut Config> {
+        match self {
+            QueryItemMut::Section(v) => Some(v),
+            QueryItemMut::Property(_) => None,
+        }
+    }
+
+    /// Returns property or `None` if item is a section
+    #[inline]
+    pub fn into_property(self) -> Option<&'a mut Property> {
+        match self {
+            QueryItemMut::Section(_) => None,
+            QueryItemMut::Property(v) => Some(v),
+        }
+    }
+}
+
+
+fn select<'a>(config: &'a Config, path: &[Segment], result: &mut Vec<QueryItem<'a>>) {
+    let segment = &path[0];
+    let last = path.len() == 1;
+
+    if last && matches!(segment.filter, Filter::All) {
+        for p in config.properties.iter().filter(|p| segment.is_name(&p.name)) {
+            result.push(QueryItem::Property(p));
+        }
+    }
+
+    let list = config.nested.iter().filter(|s| segment.is_name(&s.name));
+    for (index, s) in list.enumerate() {
+        if segment.is_match(s, index) {
+            if last {
+                result.push(QueryItem::Section(s));
+            } else {
+                select(s, &path[1 ..], result);
+            }
+        }
+    }
+}
+
+
+fn select_mut<'a>(config: &'a mut Config, path: &[Segment], result: &mut Vec<QueryItemMut<'a>>) {
+    let segment = &path[0];
+    let last = path.len() == 1;
+
+    if last && matches!(segment.filter, Filter::All) {
+        for p in config.properties.iter_mut().filter(|p| segment.is_name(&p.name)) {
+            result.push(QueryItemMut::Property(p));
+        }
+    }
+
+    let list = config.nested.iter_mut().filter(|s| segment.is_name(&s.name));
+    for (index, s) in list.enumerate() {
+        if segment.is_match(s, index) {
+            if last {
+                result.push(QueryItemMut::Section(s));
+            } else {
+                select_mut(s, &path[1 ..], result);
+            }
+        }
+    }
+}
+
+
+impl Config {
+    /// Returns sections and properties selected by the `path`
+    ///
+    /// Path contains section names separated with `/`. Last item is a section or property name.
+    /// Each section name could be followed by filter:
+    ///
+    /// - `service[pnr=1185]` - sections with property `pnr = 1185`
+    /// - `service[0]` - first section with name `service`
+    /// - `service[*]` - all sections with name `service`, same as `service`
+    ///
+    /// Name `*` matches any section or property.
+    /// If last item has no filter, properties are selected before sections.
+    ///
+    /// ```
+    /// use config::Config;
+    ///
+    /// let src = "[multiplex]\n[multiplex/service]\npnr = 1\n[multiplex/service]\npnr = 1185\nxmltv-id = ch1185\n";
+    /// let config = Config::parse(src.as_bytes()).unwrap();
+    ///
+    /// let item = config.query("multiplex/service[pnr=1185]/xmltv-id").unwrap().next().unwrap();
+    /// assert_eq!(item.as_property().unwrap().get_value(), "ch1185");
+    /// assert_eq!(config.query("multiplex[0]/service[*]").unwrap().count(), 2);
+    /// ```
+    pub fn query(&self, path: &str) -> Result<impl Iterator<Item = QueryItem<'_>>> {
+        let path = parse(path)?;
+        let mut result = Vec::new();
+        select(self, &path, &mut result);
+        Ok(result.into_iter())
+    }
+
+    /// Returns mutable sections and properties selected by the `path`.
+    /// Path syntax is same as for the `Config::query`
+    pub fn query_mut(&mut self, path: &str) -> Result<impl Iterator<Item = QueryItemMut<'_>>> {
+        let path = parse(path)?;
+        let mut result = Vec::new();
+        select_mut(self, &path, &mut result);
+        Ok(result.into_iter())
+    }
+}
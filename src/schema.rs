@@ -3,23 +3,57 @@ use std::{
         self,
         Write,
     },
-    ops::Range,
+    ops::{
+        Range,
+        RangeInclusive,
+    },
+    time::Duration,
 };
 
 use crate::config::{
+    self,
     Config,
     ConfigError,
     Result,
+    TRUE_WORDS,
+    FALSE_WORDS,
+    parse_bool,
+};
+
+use crate::units::{
+    ByteSize,
+    parse_duration,
 };
 
+use crate::number::parse_number;
+
+use crate::path::{
+    PathCheck,
+    resolve_path,
+};
+
+use crate::net::{
+    StreamUrl,
+    parse_ip,
+};
 
-pub struct Validator(Option<Box<dyn Fn(&str) -> bool>>);
+
+type ValidatorFn = dyn Fn(&str) -> bool;
+
+
+pub struct Validator(Option<Box<ValidatorFn>>);
 
 
 struct Property {
     name: String,
     description: String,
     required: bool,
+    /// Minimal and maximal number of the repeated properties
+    repeat: Option<(usize, usize)>,
+    /// Accepted values for the `info()`
+    values: Option<String>,
+    /// Existence check for the path property
+    path: Option<PathCheck>,
     validator: Validator,
 }
 
@@ -33,9 +67,17 @@ pub struct Schema {
 }
 
 
-impl From<Option<Box<dyn Fn(&str) -> bool>>> for Validator {
+/// A trait to build schema for the type.
+/// Could be implemented with `#[derive(HasSchema)]` (`derive` feature)
+pub trait HasSchema {
+    /// Returns schema for the section with `name`
+    fn schema(name: &str) -> Schema;
+}
+
+
+impl From<Option<Box<ValidatorFn>>> for Validator {
     #[inline]
-    fn from(f: Option<Box<dyn Fn(&str) -> bool>>) -> Validator {
+    fn from(f: Option<Box<ValidatorFn>>) -> Validator {
         Validator(f)
     }
 }
@@ -84,15 +126,98 @@ impl Schema {
             name: name.into(),
             description: description.into(),
             required,
+            repeat: None,
+            values: None,
+            path: None,
+            validator: validator.into(),
+        };
+        self.properties.push(property);
+    }
+
+    /// Appends information about repeatable schema parameter
+    ///
+    /// - `name` - config parameter name
+    /// - `description` - parameter description
+    /// - `min` - minimal number of the parameters. Parameter is required if `min` more than 0
+    /// - `max` - maximal number of the parameters
+    /// - `validator` - validator function or `None`. Checks each parameter
+    pub fn set_repeatable<S, B>(&mut self, name: S, description: S, min: usize, max: usize, validator: B)
+    where
+        S: Into<String>,
+        B: Into<Validator>,
+    {
+        let property = Property {
+            name: name.into(),
+            description: description.into(),
+            required: min > 0,
+            repeat: Some((min, max)),
+            values: None,
+            path: None,
             validator: validator.into(),
         };
         self.properties.push(property);
     }
 
+    /// Appends information about boolean schema parameter.
+    /// Accepted values are reported in the `info()`
+    ///
+    /// - `name` - config parameter name
+    /// - `description` - parameter description
+    /// - `required` - is parameter required
+    /// - `strict` - only `true` and `false` are accepted, see `StrictBool`
+    pub fn set_bool<S>(&mut self, name: S, description: S, required: bool, strict: bool)
+    where
+        S: Into<String>,
+    {
+        let values = if strict {
+            "true, false".to_owned()
+        } else {
+            format!("{}, {}", TRUE_WORDS.join("/"), FALSE_WORDS.join("/"))
+        };
+
+        let property = Property {
+            name: name.into(),
+            description: description.into(),
+            required,
+            repeat: None,
+            values: Some(values),
+            path: None,
+            validator: Validator::from(move |s: &str| parse_bool(s, strict).is_some()),
+        };
+        self.properties.push(property);
+    }
+
+    /// Appends information about path schema parameter.
+    /// Relative path is resolved against the file where property is defined
+    ///
+    /// - `name` - config parameter name
+    /// - `description` - parameter description
+    /// - `required` - is parameter required
+    /// - `check` - existence check for the resolved path
+    pub fn set_path<S>(&mut self, name: S, description: S, required: bool, check: PathCheck)
+    where
+        S: Into<String>,
+    {
+        let property = Property {
+            name: name.into(),
+            description: description.into(),
+            required,
+            repeat: None,
+            values: None,
+            path: Some(check),
+            validator: Validator(None),
+        };
+        self.properties.push(property);
+    }
+
     /// Appends nested schema
     #[inline]
     pub fn push(&mut self, nested: Schema) { self.nested.push(nested) }
 
+    /// Sets section description
+    #[inline]
+    pub fn set_description<S: Into<String>>(&mut self, description: S) { self.description = description.into() }
+
     fn get_nested(&self, name: &str) -> Option<&Schema> {
         for schema in &self.nested {
             if schema.name == name {
@@ -102,17 +227,61 @@ impl Schema {
         None
     }
 
+    /// Validates property value
+    fn check_property(item: &Property, property: &config::Property) -> Result<()> {
+        let valid = match &item.validator.0 {
+            Some(validator) => validator(property.get_value()),
+            None => true,
+        };
+
+        let valid = valid && match item.path {
+            Some(check) => match resolve_path(property.get_file(), property.get_value()) {
+                Some(path) => check.check(&path),
+                None => false,
+            },
+            None => true,
+        };
+
+        if ! valid {
+            return Err(ConfigError::InvalidProperty(property.get_value_location(), item.name.to_owned()));
+        }
+
+        Ok(())
+    }
+
+    /// Validates repeatable property
+    fn check_repeatable(&self, config: &Config, item: &Property, min: usize, max: usize) -> Result<()> {
+        let mut count = 0;
+
+        for property in config.get_all(&item.name) {
+            count += 1;
+            if count > max {
+                return Err(ConfigError::InvalidCount(property.get_location(), item.name.to_owned()));
+            }
+
+            Self::check_property(item, property)?;
+        }
+
+        if count == 0 && min > 0 {
+            return Err(ConfigError::MissingProperty(config.get_location(), item.name.to_owned()));
+        }
+
+        if count < min {
+            return Err(ConfigError::InvalidCount(config.get_location(), item.name.to_owned()));
+        }
+
+        Ok(())
+    }
+
     /// Validates config with schema
     pub fn check(&self, config: &Config) ->  Result<()> {
         for item in &self.properties {
-            if let Some(property) = config.get_property(&item.name) {
-                if let Some(validator) = &item.validator.0 {
-                    if ! validator(&property.get_value()) {
-                        return Err(ConfigError::InvalidProperty(property.get_line(), item.name.to_owned()));
-                    }
-                }
+            if let Some((min, max)) = item.repeat {
+                self.check_repeatable(config, item, min, max)?;
+            } else if let Some(property) = config.get_property(&item.name) {
+                Self::check_property(item, property)?;
             } else if item.required {
-                return Err(ConfigError::MissingProperty(config.get_line(), item.name.to_owned()));
+                return Err(ConfigError::MissingProperty(config.get_location(), item.name.to_owned()));
             }
         }
 
@@ -131,7 +300,10 @@ impl Schema {
         }
 
         for item in &self.properties {
-            writeln!(result, "{} = {}", &item.name, &item.description)?;
+            match &item.values {
+                Some(values) => writeln!(result, "{} = {} ({})", &item.name, &item.description, values)?,
+                None => writeln!(result, "{} = {}", &item.name, &item.description)?,
+            }
         }
 
         if ! self.nested.is_empty() {
@@ -161,14 +333,56 @@ impl Schema {
         result
     }
 
-    /// Range validator
+    /// Range validator. End of the range is included.
+    /// Accepts same integer literals as `FromProperty`
     pub fn range(r: Range<usize>) -> impl Fn(&str) -> bool {
         move |s: &str| -> bool {
-            let (skip, radix) = if s.starts_with("0x") { (2, 16u32) } else { (0, 10u32) };
-            match usize::from_str_radix(&s[skip ..], radix) {
+            match parse_number::<usize>(s) {
                 Ok(v) => (v >= r.start) && (v <= r.end),
                 _ => false,
             }
         }
     }
+
+    /// Duration validator. Example: `Schema::duration(Duration::from_secs(1) ..= Duration::from_secs(3600))`
+    pub fn duration(r: RangeInclusive<Duration>) -> impl Fn(&str) -> bool {
+        move |s: &str| -> bool {
+            match parse_duration(s) {
+                Some(v) => r.contains(&v),
+                None => false,
+            }
+        }
+    }
+
+    /// Byte size validator. Range is defined in bytes
+    pub fn byte_size(r: RangeInclusive<u64>) -> impl Fn(&str) -> bool {
+        move |s: &str| -> bool {
+            match ByteSize::parse(s) {
+                Some(v) => r.contains(&v.get()),
+                None => false,
+            }
+        }
+    }
+
+    /// Multicast address validator.
+    /// Accepts IP address, socket address, or stream address with multicast IP address
+    pub fn multicast() -> impl Fn(&str) -> bool {
+        move |s: &str| -> bool {
+            match parse_ip(s) {
+                Some(v) => v.is_multicast(),
+                None => false,
+            }
+        }
+    }
+
+    /// Stream address validator. Example: `Schema::scheme(&["udp", "rtp", "http"])`
+    pub fn scheme(list: &[&str]) -> impl Fn(&str) -> bool {
+        let list: Vec<String> = list.iter().map(|v| v.to_ascii_lowercase()).collect();
+        move |s: &str| -> bool {
+            match StreamUrl::parse(s) {
+                Some(v) => list.iter().any(|scheme| scheme == v.get_scheme()),
+                None => false,
+            }
+        }
+    }
 }
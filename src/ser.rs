@@ -0,0 +1,313 @@
+use serde::ser::{
+    self,
+    Serialize,
+};
+
+use crate::config::{
+    Config,
+    ConfigError,
+    Result,
+};
+
+
+impl ser::Error for ConfigError {
+    #[inline]
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        ConfigError::Custom(msg.to_string())
+    }
+}
+
+
+/// Serializes user type into the config
+///
+/// - structs and maps are serialized into the sections
+/// - scalar values are serialized into the properties with `ToString`
+/// - `Vec<T>` is serialized into the repeated sections or properties with same name,
+///   empty `Vec<T>` is serialized into the empty inline array `name = []`
+/// - `None` values are skipped
+///
+/// ```
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Service {
+///     pnr: u16,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Multiplex {
+///     tsid: u16,
+///     service: Vec<Service>,
+/// }
+///
+/// let multiplex = Multiplex {
+///     tsid: 1,
+///     service: vec![Service { pnr: 1 }, Service { pnr: 2 }],
+/// };
+///
+/// let mut config = config::Config::new("");
+/// config.push(config::to_config("multiplex", &multiplex).unwrap());
+///
+/// let mut s = Vec::<u8>::new();
+/// config.dump(&mut s).unwrap();
+/// assert_eq!(s.as_slice(), b"
+/// [multiplex]
+/// tsid = 1
+///
+/// [multiplex/service]
+/// pnr = 1
+///
+/// [multiplex/service]
+/// pnr = 2
+/// ");
+/// ```
+pub fn to_config<S, T>(name: S, value: &T) -> Result<Config>
+where
+    S: Into<String>,
+    T: Serialize + ?Sized,
+{
+    match value.serialize(ValueSerializer)? {
+        Value::Section(mut config) => {
+            config.name = name.into();
+            Ok(config)
+        }
+        _ => Err(ConfigError::Custom("expected struct or map".to_owned())),
+    }
+}
+
+
+/// Serialized value
+enum Value {
+    None,
+    Scalar(String),
+    Section(Config),
+    List(Vec<Value>),
+}
+
+
+/// Appends value into the config
+fn insert(config: &mut Config, name: &str, value: Value) {
+    match value {
+        Value::None => {}
+        Value::Scalar(v) => config.set(name, v),
+        Value::Section(mut v) => {
+            v.name = name.to_owned();
+            config.push(v);
+        }
+        Value::List(list) if list.is_empty() => {
+            config.set_array(name, std::iter::empty::<String>());
+        }
+        Value::List(list) => {
+            for v in list {
+                insert(config, name, v);
+            }
+        }
+    }
+}
+
+
+#[inline]
+fn unsupported(kind: &str) -> ConfigError {
+    ConfigError::Custom(format!("{} is not supported", kind))
+}
+
+
+struct ValueSerializer;
+
+
+macro_rules! serialize_scalar {
+    ( $( $method:ident : $t:ty ),* ) => {
+        $( #[inline]
+        fn $method(self, v: $t) -> Result<Value> {
+            Ok(Value::Scalar(v.to_string()))
+        } )*
+    };
+}
+
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = ConfigError;
+
+    type SerializeSeq = ListSerializer;
+    type SerializeTuple = ListSerializer;
+    type SerializeTupleStruct = ListSerializer;
+    type SerializeTupleVariant = ser::Impossible<Value, ConfigError>;
+    type SerializeMap = SectionSerializer;
+    type SerializeStruct = SectionSerializer;
+    type SerializeStructVariant = ser::Impossible<Value, ConfigError>;
+
+    serialize_scalar!(
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_f32: f32,
+        serialize_f64: f64,
+        serialize_char: char,
+        serialize_str: &str
+    );
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<Value> {
+        Err(unsupported("bytes"))
+    }
+
+    #[inline]
+    fn serialize_none(self) -> Result<Value> { Ok(Value::None) }
+
+    #[inline]
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Value> {
+        value.serialize(self)
+    }
+
+    #[inline]
+    fn serialize_unit(self) -> Result<Value> { Ok(Value::Scalar(String::new())) }
+
+    #[inline]
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value> {
+        self.serialize_unit()
+    }
+
+    #[inline]
+    fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<Value> {
+        Ok(Value::Scalar(variant.to_owned()))
+    }
+
+    #[inline]
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<Value> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(self, _name: &'static str, _index: u32, _variant: &'static str, _value: &T) -> Result<Value> {
+        Err(unsupported("enum variant with value"))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<ListSerializer> {
+        Ok(ListSerializer(Vec::with_capacity(len.unwrap_or(0))))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<ListSerializer> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<ListSerializer> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(self, _name: &'static str, _index: u32, _variant: &'static str, _len: usize) -> Result<Self::SerializeTupleVariant> {
+        Err(unsupported("enum variant with value"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<SectionSerializer> {
+        Ok(SectionSerializer {
+            config: Config::new(""),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SectionSerializer> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(self, _name: &'static str, _index: u32, _variant: &'static str, _len: usize) -> Result<Self::SerializeStructVariant> {
+        Err(unsupported("enum variant with value"))
+    }
+}
+
+
+/// Collects items of the sequence
+struct ListSerializer(Vec<Value>);
+
+
+impl ser::SerializeSeq for ListSerializer {
+    type Ok = Value;
+    type Error = ConfigError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.0.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    #[inline]
+    fn end(self) -> Result<Value> { Ok(Value::List(self.0)) }
+}
+
+
+impl ser::SerializeTuple for ListSerializer {
+    type Ok = Value;
+    type Error = ConfigError;
+
+    #[inline]
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    #[inline]
+    fn end(self) -> Result<Value> { ser::SerializeSeq::end(self) }
+}
+
+
+impl ser::SerializeTupleStruct for ListSerializer {
+    type Ok = Value;
+    type Error = ConfigError;
+
+    #[inline]
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    #[inline]
+    fn end(self) -> Result<Value> { ser::SerializeSeq::end(self) }
+}
+
+
+/// Collects properties and nested sections
+struct SectionSerializer {
+    config: Config,
+    key: Option<String>,
+}
+
+
+impl ser::SerializeMap for SectionSerializer {
+    type Ok = Value;
+    type Error = ConfigError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        match key.serialize(ValueSerializer)? {
+            Value::Scalar(v) => {
+                self.key = Some(v);
+                Ok(())
+            }
+            _ => Err(ConfigError::Custom("key should be a string".to_owned())),
+        }
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let key = self.key.take()
+            .ok_or_else(|| ConfigError::Custom("key is missing".to_owned()))?;
+        insert(&mut self.config, &key, value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    #[inline]
+    fn end(self) -> Result<Value> { Ok(Value::Section(self.config)) }
+}
+
+
+impl ser::SerializeStruct for SectionSerializer {
+    type Ok = Value;
+    type Error = ConfigError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<()> {
+        insert(&mut self.config, key, value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    #[inline]
+    fn end(self) -> Result<Value> { Ok(Value::Section(self.config)) }
+}
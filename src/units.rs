@@ -0,0 +1,233 @@
+use std::{
+    convert::TryFrom,
+    fmt,
+    time::Duration,
+};
+
+use crate::config::{
+    ConfigError,
+    FromProperty,
+    Property,
+    Result,
+    ScalarProperty,
+    ToProperty,
+};
+
+
+/// Duration units in nanoseconds
+const DURATION_UNITS: &[(&str, u128)] = &[
+    ("d", 86_400_000_000_000),
+    ("h", 3_600_000_000_000),
+    ("m", 60_000_000_000),
+    ("s", 1_000_000_000),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
+
+/// Byte size units. Single-letter units are accepted on parsing only
+const SIZE_UNITS: &[(&str, u128)] = &[
+    ("TiB", 1 << 40),
+    ("TB", 1_000_000_000_000),
+    ("GiB", 1 << 30),
+    ("GB", 1_000_000_000),
+    ("MiB", 1 << 20),
+    ("MB", 1_000_000),
+    ("KiB", 1 << 10),
+    ("KB", 1_000),
+    ("T", 1_000_000_000_000),
+    ("G", 1_000_000_000),
+    ("M", 1_000_000),
+    ("K", 1_000),
+    ("B", 1),
+];
+
+
+/// Splits number with optional fraction from the beginning of the text.
+/// Returns integer part, fraction digits, and remaining text
+fn split_number(text: &str) -> Option<(&str, &str, &str)> {
+    let end = text.find(|c: char| ! c.is_ascii_digit()).unwrap_or(text.len());
+    let (integer, tail) = text.split_at(end);
+
+    let (fraction, tail) = match tail.strip_prefix('.') {
+        Some(tail) => {
+            let end = tail.find(|c: char| ! c.is_ascii_digit()).unwrap_or(tail.len());
+            tail.split_at(end)
+        }
+        None => ("", tail),
+    };
+
+    if integer.is_empty() && fraction.is_empty() {
+        return None;
+    }
+
+    Some((integer, fraction, tail))
+}
+
+
+/// Returns `integer.fraction * unit`.
+/// Fraction is limited to 9 digits, remainder less than 1 is truncated
+fn scale(integer: &str, fraction: &str, unit: u128) -> Option<u128> {
+    let mut result = if integer.is_empty() { 0 } else { integer.parse::<u128>().ok()? };
+    result = result.checked_mul(unit)?;
+
+    let mut divider = 1u128;
+    let mut value = 0u128;
+    for c in fraction.chars().take(9) {
+        divider *= 10;
+        value = value * 10 + u128::from(c as u8 - b'0');
+    }
+
+    result.checked_add(value * unit / divider)
+}
+
+
+/// Parses duration: sequence of numbers with units `d`, `h`, `m`, `s`, `ms`, `us`, `ns`.
+/// Example: `1h30m`, `250ms`, `1.5s`. Value `0` is allowed without unit
+pub(crate) fn parse_duration(value: &str) -> Option<Duration> {
+    if value == "0" {
+        return Some(Duration::ZERO);
+    }
+
+    if value.is_empty() {
+        return None;
+    }
+
+    let mut text = value;
+    let mut result = 0u128;
+
+    while ! text.is_empty() {
+        let (integer, fraction, tail) = split_number(text)?;
+        let (suffix, unit) = DURATION_UNITS.iter()
+            .filter(|(suffix, _)| tail.starts_with(suffix))
+            .max_by_key(|(suffix, _)| suffix.len())?;
+        result = result.checked_add(scale(integer, fraction, *unit)?)?;
+        text = &tail[suffix.len() ..];
+    }
+
+    let secs = u64::try_from(result / 1_000_000_000).ok()?;
+    Some(Duration::new(secs, (result % 1_000_000_000) as u32))
+}
+
+
+/// Returns duration in the canonical form. Example: `1h30m`, `1s500ms`
+pub(crate) fn format_duration(value: Duration) -> String {
+    let mut nanos = value.as_nanos();
+    if nanos == 0 {
+        return "0s".to_owned();
+    }
+
+    let mut result = String::new();
+    for (suffix, unit) in DURATION_UNITS {
+        if nanos >= *unit {
+            result.push_str(&(nanos / unit).to_string());
+            result.push_str(suffix);
+            nanos %= unit;
+        }
+    }
+
+    result
+}
+
+
+/// Size in bytes
+///
+/// Value in the config is a number with optional unit suffix:
+///
+/// - `B` or without suffix - bytes
+/// - `K`, `KB`, `M`, `MB`, `G`, `GB`, `T`, `TB` - SI units, power of 1000
+/// - `KiB`, `MiB`, `GiB`, `TiB` - IEC units, power of 1024
+///
+/// Suffix is case-insensitive. Number could have a fraction, for example `1.5GiB`.
+/// Display writes size with largest unit without fraction
+///
+/// ```
+/// use config::{Config, ByteSize};
+///
+/// let config = Config::parse("buffer = 16MiB".as_bytes()).unwrap();
+/// let buffer: ByteSize = config.get("buffer").unwrap();
+/// assert_eq!(buffer.get(), 16 * 1024 * 1024);
+/// assert_eq!(buffer.to_string(), "16MiB");
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ByteSize(pub u64);
+
+
+impl ByteSize {
+    /// Returns size in bytes
+    #[inline]
+    pub fn get(self) -> u64 { self.0 }
+
+    /// Parses size with unit suffix
+    pub fn parse(value: &str) -> Option<ByteSize> {
+        let (integer, fraction, tail) = split_number(value)?;
+        let tail = tail.trim_start();
+
+        let unit = if tail.is_empty() {
+            1
+        } else {
+            SIZE_UNITS.iter().find(|(suffix, _)| tail.eq_ignore_ascii_case(suffix))?.1
+        };
+
+        let result = scale(integer, fraction, unit)?;
+        u64::try_from(result).ok().map(ByteSize)
+    }
+}
+
+
+impl fmt::Display for ByteSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = u128::from(self.0);
+
+        for (suffix, unit) in SIZE_UNITS {
+            if suffix.len() > 1 && value != 0 && value % unit == 0 {
+                return write!(f, "{}{}", value / unit, suffix);
+            }
+        }
+
+        write!(f, "{}", value)
+    }
+}
+
+
+impl From<u64> for ByteSize {
+    #[inline]
+    fn from(value: u64) -> ByteSize { ByteSize(value) }
+}
+
+
+/// Duration with units. Example: `5s`, `250ms`, `1h30m`
+impl<'a> FromProperty<'a> for Duration {
+    fn from_property(p: &'a Property) -> Result<Duration> {
+        parse_duration(&p.value)
+            .ok_or_else(|| ConfigError::InvalidProperty(p.get_value_location(), p.name.to_owned()))
+    }
+}
+
+
+/// Duration in the canonical form. Example: `1h30m`, `1s500ms`
+impl ToProperty for Duration {
+    #[inline]
+    fn to_property(&self, p: &mut Property) { p.set_scalar(format_duration(*self)) }
+}
+
+
+impl<'a> FromProperty<'a> for ByteSize {
+    fn from_property(p: &'a Property) -> Result<ByteSize> {
+        ByteSize::parse(&p.value)
+            .ok_or_else(|| ConfigError::InvalidProperty(p.get_value_location(), p.name.to_owned()))
+    }
+}
+
+
+impl ToProperty for ByteSize {
+    #[inline]
+    fn to_property(&self, p: &mut Property) { p.set_scalar(self.to_string()) }
+}
+
+
+impl ScalarProperty for Duration {}
+
+
+impl ScalarProperty for ByteSize {}
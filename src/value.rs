@@ -0,0 +1,453 @@
+use std::{
+    borrow::Cow,
+    fmt::Write,
+    ops::Range,
+};
+
+use crate::config::{
+    ConfigError,
+    Location,
+    Result,
+};
+
+
+fn parse_unicode(location: &Location, chars: &mut std::str::Chars) -> Result<char> {
+    if chars.next() != Some('{') {
+        return Err(ConfigError::InvalidFormat(location.clone()));
+    }
+
+    let mut code = 0u32;
+    let mut digits = 0;
+
+    loop {
+        let c = chars.next().ok_or_else(|| ConfigError::InvalidFormat(location.clone()))?;
+        if c == '}' {
+            break;
+        }
+
+        let digit = c.to_digit(16).ok_or_else(|| ConfigError::InvalidFormat(location.clone()))?;
+        digits += 1;
+        if digits > 6 {
+            return Err(ConfigError::InvalidFormat(location.clone()));
+        }
+        code = (code << 4) | digit;
+    }
+
+    if digits == 0 {
+        return Err(ConfigError::InvalidFormat(location.clone()));
+    }
+
+    std::char::from_u32(code).ok_or_else(|| ConfigError::InvalidFormat(location.clone()))
+}
+
+
+/// Parses quoted string. `token` should starts with `"` symbol.
+/// Returns unescaped string and remaining part of the token after closing quote
+fn parse_quoted<'a>(location: &Location, token: &'a str) -> Result<(String, &'a str)> {
+    let mut result = String::new();
+    let mut chars = token[1 ..].chars();
+
+    loop {
+        let c = chars.next().ok_or_else(|| ConfigError::InvalidFormat(location.clone()))?;
+        match c {
+            '"' => break,
+            '\\' => {
+                let c = chars.next().ok_or_else(|| ConfigError::InvalidFormat(location.clone()))?;
+                match c {
+                    'n' => result.push('\n'),
+                    't' => result.push('\t'),
+                    'r' => result.push('\r'),
+                    '0' => result.push('\0'),
+                    '"' => result.push('"'),
+                    '\\' => result.push('\\'),
+                    'u' => result.push(parse_unicode(location, &mut chars)?),
+                    _ => return Err(ConfigError::InvalidFormat(location.clone())),
+                }
+            }
+            _ => result.push(c),
+        }
+    }
+
+    Ok((result, chars.as_str()))
+}
+
+
+/// Returns length of the quoted string including quotes. `token` should starts with `"` symbol
+fn quoted_len(token: &str) -> Option<usize> {
+    let mut chars = token.char_indices().skip(1);
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Some(i + 1),
+            '\\' => { chars.next(); }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+
+/// Returns length of the inline array or map including brackets.
+/// `token` should starts with `[` or `{` symbol
+fn inline_len(token: &str) -> Option<usize> {
+    let close = if token.starts_with('[') { ']' } else { '}' };
+    let mut skip = 1;
+
+    while let Some(i) = token[skip ..].find(['"', close]) {
+        let i = i + skip;
+        if token[i ..].starts_with('"') {
+            skip = i + quoted_len(&token[i ..])?;
+        } else {
+            return Some(i + 1);
+        }
+    }
+
+    None
+}
+
+
+/// Checks if value token is an inline array or map
+#[inline]
+pub(crate) fn is_inline(token: &str) -> bool {
+    token.starts_with('[') || token.starts_with('{')
+}
+
+
+/// Splits token to the value and trailing comment.
+/// Comment starts with `#` symbol at the beginning of the token or after white-space.
+/// `#` inside quoted value or inline array is a part of the value.
+pub(crate) fn split_comment<'a>(location: &Location, token: &'a str) -> Result<(&'a str, Option<&'a str>)> {
+    let token = token.trim();
+
+    let skip = if token.starts_with('"') || is_inline(token) {
+        let skip = if token.starts_with('"') { quoted_len(token) } else { inline_len(token) }
+            .ok_or_else(|| ConfigError::InvalidFormat(location.clone()))?;
+        let tail = token[skip ..].trim_start();
+        if ! tail.is_empty() && ! tail.starts_with('#') {
+            return Err(ConfigError::InvalidFormat(location.clone()));
+        }
+        skip
+    } else {
+        0
+    };
+
+    let mut prev = ' ';
+    for (i, c) in token[skip ..].char_indices() {
+        if c == '#' && prev.is_whitespace() {
+            let i = i + skip;
+            return Ok((token[.. i].trim_end(), Some(token[i + 1 ..].trim())));
+        }
+        prev = c;
+    }
+
+    Ok((token, None))
+}
+
+
+/// Value representation in the config file
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Style {
+    /// Single-line bare or quoted value
+    Line,
+    /// Bare value splitted with trailing `\` symbol. Contains offsets of the line breaks
+    Continuation(Vec<usize>),
+    /// Raw multi-line value between `<<TAG` and `TAG` lines
+    Heredoc(String),
+    /// Inline array or map. Value is stored in the config syntax
+    Inline,
+}
+
+
+/// Item of the inline array or map. Ranges are the locations in the node text
+pub(crate) struct Item {
+    /// Key of the map item. `None` for array items
+    pub key: Option<String>,
+    pub key_range: Range<usize>,
+    pub value: String,
+    pub value_range: Range<usize>,
+}
+
+
+/// Parsed inline array or map
+pub(crate) enum Inline {
+    Array(Vec<Item>),
+    Map(Vec<Item>),
+}
+
+
+/// Returns heredoc tag if value token started with `<<TAG`
+pub(crate) fn heredoc_tag(token: &str) -> Option<&str> {
+    let tag = token.strip_prefix("<<")?;
+    if ! tag.is_empty() && tag.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        Some(tag)
+    } else {
+        None
+    }
+}
+
+
+/// Parses property value. `token` is a part of the line after `=` delimiter.
+/// Bare values are trimmed, quoted values are unescaped.
+pub(crate) fn parse_value(location: &Location, token: &str) -> Result<String> {
+    let token = token.trim();
+
+    if ! token.starts_with('"') {
+        return Ok(token.to_owned());
+    }
+
+    let (value, tail) = parse_quoted(location, token)?;
+    if ! tail.trim_start().is_empty() {
+        return Err(ConfigError::InvalidFormat(location.clone()));
+    }
+
+    Ok(value)
+}
+
+
+/// Parses item of the inline array or map. Bare item ends with any of the `delimiters`.
+/// Returns item value, item range, and remaining part of the text
+fn parse_item<'a>(location: &Location, text: &'a str, skip: usize, delimiters: &[char]) -> Result<(String, Range<usize>, &'a str)> {
+    let token = text.trim_start();
+    let begin = skip + text.len() - token.len();
+
+    if token.starts_with('"') {
+        let end = quoted_len(token).ok_or_else(|| ConfigError::InvalidFormat(location.clone()))?;
+        let (value, tail) = parse_quoted(location, &token[.. end])?;
+        debug_assert!(tail.is_empty());
+        return Ok((value, begin .. begin + end, &token[end ..]));
+    }
+
+    let end = token.find(delimiters).unwrap_or(token.len());
+    let value = token[.. end].trim_end();
+    if value.is_empty() {
+        return Err(ConfigError::InvalidFormat(location.clone()));
+    }
+
+    Ok((value.to_owned(), begin .. begin + value.len(), &token[value.len() ..]))
+}
+
+
+/// Parses inline array `[a, b, c]` or map `{a = 1, b = 2}`.
+/// Items are bare or quoted values separated with `,`. Trailing `,` is allowed.
+/// `skip` - offset of the `token` in the node text
+pub(crate) fn parse_inline(location: &Location, token: &str, skip: usize) -> Result<Inline> {
+    let is_map = token.starts_with('{');
+    let close = if is_map { '}' } else { ']' };
+
+    if token.len() < 2 || ! token.ends_with(close) {
+        return Err(ConfigError::InvalidFormat(location.clone()));
+    }
+
+    let inner = &token[1 .. token.len() - 1];
+
+    let mut items = Vec::new();
+    let mut text = inner;
+
+    while ! text.trim().is_empty() {
+        let offset = skip + token.len() - 1 - text.len();
+
+        let item = if is_map {
+            let (key, key_range, tail) = parse_item(location, text, offset, &['=', ','])?;
+            let tail = tail.trim_start().strip_prefix('=')
+                .ok_or_else(|| ConfigError::InvalidFormat(location.clone()))?;
+            let offset = skip + token.len() - 1 - tail.len();
+            let (value, value_range, tail) = parse_item(location, tail, offset, &[','])?;
+            text = tail;
+            Item { key: Some(key), key_range, value, value_range }
+        } else {
+            let (value, value_range, tail) = parse_item(location, text, offset, &[','])?;
+            text = tail;
+            Item { key: None, key_range: value_range.clone(), value, value_range }
+        };
+
+        items.push(item);
+
+        let tail = text.trim_start();
+        if tail.is_empty() {
+            break;
+        }
+        text = tail.strip_prefix(',')
+            .ok_or_else(|| ConfigError::InvalidFormat(location.clone()))?;
+    }
+
+    if is_map {
+        Ok(Inline::Map(items))
+    } else {
+        Ok(Inline::Array(items))
+    }
+}
+
+
+/// Checks if value could be stored without quotes
+fn is_bare(value: &str) -> bool {
+    if value.starts_with('"') || value.starts_with("<<") || value.ends_with('\\') || is_inline(value) {
+        return false;
+    }
+
+    if value.trim() != value {
+        return false;
+    }
+
+    ! value.chars().any(|c| c.is_control() || c == '#')
+}
+
+
+/// Returns quoted and escaped value.
+/// `expand` - value is expanded on reading, so the `$` symbol is doubled
+fn quote_string(value: &str, expand: bool) -> String {
+    let mut result = String::with_capacity(value.len() + 2);
+    result.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\t' => result.push_str("\\t"),
+            '\r' => result.push_str("\\r"),
+            '$' if expand => result.push_str("$$"),
+            c if c.is_control() => { write!(result, "\\u{{{:x}}}", c as u32).unwrap(); }
+            c => result.push(c),
+        }
+    }
+
+    result.push('"');
+    result
+}
+
+
+/// Returns value suitable to store in the config file.
+/// Quotes and escapes value if it could not be stored as a bare string.
+/// The `$` symbol is doubled to keep it out of references expansion.
+fn quote_value(value: &str) -> Cow<'_, str> {
+    if ! is_bare(value) {
+        return Cow::Owned(quote_string(value, true));
+    }
+
+    if value.contains('$') {
+        Cow::Owned(value.replace('$', "$$"))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+
+/// Checks if item of the inline array or map should be quoted
+fn is_quoted_item(value: &str) -> bool {
+    value.is_empty() || value.contains([',', '=', '[', ']', '{', '}', '"'])
+}
+
+
+/// Returns item suitable to store in the inline array or map
+fn quote_item(value: &str) -> Cow<'_, str> {
+    if is_quoted_item(value) {
+        Cow::Owned(quote_string(value, true))
+    } else {
+        quote_value(value)
+    }
+}
+
+
+/// Returns key suitable to store in the inline map.
+/// Keys are not expanded, so the `$` symbol is kept as is
+fn quote_key(value: &str) -> Cow<'_, str> {
+    if is_quoted_item(value) || ! is_bare(value) {
+        Cow::Owned(quote_string(value, false))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+
+/// Returns inline array `[a, b, c]`
+pub(crate) fn format_array<'a, I>(items: I) -> String
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let items: Vec<Cow<str>> = items.into_iter().map(quote_item).collect();
+    format!("[{}]", items.join(", "))
+}
+
+
+/// Returns inline map `{a = 1, b = 2}`
+pub(crate) fn format_map<'a, I>(items: I) -> String
+where
+    I: IntoIterator<Item = (&'a str, &'a str)>,
+{
+    let items: Vec<String> = items.into_iter()
+        .map(|(key, value)| format!("{} = {}", quote_key(key), quote_item(value)))
+        .collect();
+    format!("{{{}}}", items.join(", "))
+}
+
+
+/// Checks if segment could be written on the continued line:
+/// leading white-spaces are trimmed and quotes or brackets start a new value
+fn is_continued(segment: &str) -> bool {
+    ! segment.starts_with(|c: char| c.is_whitespace() || c == '"' || c == '[' || c == '{')
+}
+
+
+fn format_continuation(value: &str, breaks: &[usize]) -> Option<String> {
+    if ! is_bare(value) || value.contains('$') {
+        return None;
+    }
+
+    let mut result = String::with_capacity(value.len() + breaks.len() * 6);
+    let mut skip = 0;
+
+    for &next in breaks {
+        if next < skip || ! value.is_char_boundary(next) {
+            return None;
+        }
+        if skip != 0 && ! is_continued(&value[skip ..]) {
+            return None;
+        }
+        result.push_str(&value[skip .. next]);
+        result.push_str("\\\n    ");
+        skip = next;
+    }
+
+    let tail = &value[skip ..];
+    if skip != 0 && ! is_continued(tail) {
+        return None;
+    }
+    result.push_str(tail);
+
+    Some(result)
+}
+
+
+fn format_heredoc(value: &str, tag: &str, comment: Option<&str>) -> Option<String> {
+    if value.contains('\r') || value.split('\n').any(|line| line.trim() == tag) {
+        return None;
+    }
+
+    Some(format!("<<{}{}\n{}\n{}", tag, format_comment(comment), value, tag))
+}
+
+
+/// Returns trailing comment suitable to append to the line
+pub(crate) fn format_comment(comment: Option<&str>) -> String {
+    match comment {
+        Some(v) if ! v.is_empty() => format!(" # {}", v),
+        Some(_) => " #".to_owned(),
+        None => String::new(),
+    }
+}
+
+
+/// Returns value in the same form as it was defined in the config file with trailing comment.
+/// Fallbacks to the single-line value if form could not keep value as is.
+pub(crate) fn format_value(value: &str, style: &Style, comment: Option<&str>) -> String {
+    let result = match style {
+        Style::Line => None,
+        Style::Continuation(breaks) => format_continuation(value, breaks)
+            .map(|v| v + &format_comment(comment)),
+        Style::Heredoc(tag) => format_heredoc(value, tag, comment),
+        Style::Inline => Some(value.to_owned() + &format_comment(comment)),
+    };
+
+    result.unwrap_or_else(|| quote_value(value).into_owned() + &format_comment(comment))
+}
@@ -0,0 +1,9 @@
+use config::Config;
+
+
+/// Returns config dump as string
+pub fn dump(config: &Config) -> String {
+    let mut s = Vec::<u8>::new();
+    config.dump(&mut s).unwrap();
+    String::from_utf8(s).unwrap()
+}
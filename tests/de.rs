@@ -0,0 +1,289 @@
+#![cfg(feature = "serde")]
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use config::{
+    Config,
+    ConfigError,
+    Diagnostic,
+    from_config,
+};
+
+
+const SRC: &str = r#"
+xmltv = /opt/discovery.xml
+debug = true
+
+[multiplex]
+tsid = 1
+mode = dvb-t
+
+[multiplex/service]
+pnr = 1
+name = Channel 1
+
+[multiplex/service]
+pnr = 2
+name = Channel 2
+xmltv-id = ch2
+
+[multiplex]
+tsid = 2
+mode = dvb-s
+"#;
+
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+struct Service {
+    pnr: u16,
+    name: String,
+    xmltv_id: Option<String>,
+}
+
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+enum Mode {
+    DvbT,
+    DvbS,
+}
+
+
+#[derive(Debug, Deserialize)]
+struct Multiplex {
+    tsid: u16,
+    mode: Mode,
+    #[serde(default)]
+    service: Vec<Service>,
+}
+
+
+#[derive(Debug, Deserialize)]
+struct Main<'a> {
+    xmltv: &'a str,
+    debug: bool,
+    verbose: Option<bool>,
+    multiplex: Vec<Multiplex>,
+}
+
+
+#[test]
+fn test_de() {
+    let config = Config::parse(SRC.as_bytes()).unwrap();
+    let main: Main = from_config(&config).unwrap();
+
+    assert_eq!(main.xmltv, "/opt/discovery.xml");
+    assert!(main.debug);
+    assert_eq!(main.verbose, None);
+
+    assert_eq!(main.multiplex.len(), 2);
+    assert_eq!(main.multiplex[0].tsid, 1);
+    assert_eq!(main.multiplex[0].mode, Mode::DvbT);
+    assert_eq!(main.multiplex[0].service, vec![
+        Service { pnr: 1, name: "Channel 1".to_owned(), xmltv_id: None },
+        Service { pnr: 2, name: "Channel 2".to_owned(), xmltv_id: Some("ch2".to_owned()) },
+    ]);
+    assert_eq!(main.multiplex[1].mode, Mode::DvbS);
+    assert!(main.multiplex[1].service.is_empty());
+}
+
+
+#[test]
+fn test_de_map() {
+    let config = Config::parse(SRC.as_bytes()).unwrap();
+    let multiplex = config.iter().next().unwrap();
+    let service = multiplex.iter().nth(1).unwrap();
+
+    let map: HashMap<String, String> = from_config(service).unwrap();
+    assert_eq!(map.len(), 3);
+    assert_eq!(map["xmltv-id"], "ch2");
+}
+
+
+#[test]
+fn test_de_invalid_property() {
+    let config = Config::parse("[multiplex]\ntsid = 100000\nmode = dvb-t\n".as_bytes()).unwrap();
+    let result = from_config::<Multiplex>(config.iter().next().unwrap());
+    match result {
+        Err(ConfigError::OutOfRange(location, name)) => {
+            assert_eq!(location.get_line(), 2);
+            assert_eq!(name, "tsid");
+        }
+        _ => unreachable!(),
+    }
+
+    let config = Config::parse("[multiplex]\ntsid = 1\nmode = dvb-c\n".as_bytes()).unwrap();
+    let result = from_config::<Multiplex>(config.iter().next().unwrap());
+    match result {
+        Err(ConfigError::InvalidProperty(location, name)) => {
+            assert_eq!(location.get_line(), 3);
+            assert_eq!(name, "mode");
+        }
+        _ => unreachable!(),
+    }
+}
+
+
+#[test]
+fn test_de_missing_property() {
+    let config = Config::parse("\n[multiplex]\nmode = dvb-t\n".as_bytes()).unwrap();
+    let result = from_config::<Multiplex>(config.iter().next().unwrap());
+    match result {
+        Err(ConfigError::MissingProperty(location, name)) => {
+            assert_eq!(location.get_line(), 2);
+            assert_eq!(name, "tsid");
+        }
+        _ => unreachable!(),
+    }
+}
+
+
+#[test]
+fn test_de_inline() {
+    #[derive(Deserialize)]
+    struct Address<'a> {
+        host: &'a str,
+        port: u16,
+    }
+
+    #[derive(Deserialize)]
+    struct Main<'a> {
+        pids: Vec<u16>,
+        #[serde(borrow)]
+        langs: HashMap<&'a str, u8>,
+        address: Address<'a>,
+    }
+
+    let src = "pids = [100, 0x200]\nlangs = {rus = 1, eng = 2}\naddress = {host = 127.0.0.1, port = 8000}\n";
+    let config = Config::parse(src.as_bytes()).unwrap();
+    let main: Main = from_config(&config).unwrap();
+
+    assert_eq!(main.pids, [100, 0x200]);
+    assert_eq!(main.langs["eng"], 2);
+    assert_eq!(main.address.host, "127.0.0.1");
+    assert_eq!(main.address.port, 8000);
+
+    let config = Config::parse("pids = [100, 70000]\nlangs = {}\naddress = {}\n".as_bytes()).unwrap();
+    match from_config::<Main>(&config) {
+        Err(ConfigError::OutOfRange(location, name)) => {
+            assert_eq!(location.get_column(), 14);
+            assert_eq!(name, "pids");
+        }
+        _ => unreachable!(),
+    }
+}
+
+
+#[test]
+fn test_de_option() {
+    #[derive(Deserialize)]
+    struct Main {
+        output: Option<Vec<String>>,
+        service: Option<Vec<Service>>,
+        input: Option<Vec<String>>,
+    }
+
+    let src = "output = a\noutput = b\n[service]\npnr = 1\nname = A\n[service]\npnr = 2\nname = B\n";
+    let config = Config::parse(src.as_bytes()).unwrap();
+    let main: Main = from_config(&config).unwrap();
+
+    assert_eq!(main.output, Some(vec!["a".to_owned(), "b".to_owned()]));
+    let service = main.service.unwrap();
+    assert_eq!(service.len(), 2);
+    assert_eq!(service[1].pnr, 2);
+    assert_eq!(main.input, None);
+
+    let config = Config::parse("[service]\npnr = 1\nname = A\n[input]\n".as_bytes()).unwrap();
+    match from_config::<Main>(&config) {
+        Err(ConfigError::InvalidProperty(location, name)) => {
+            assert_eq!(location.get_line(), 4);
+            assert_eq!(name, "input");
+        }
+        _ => unreachable!(),
+    }
+}
+
+
+#[test]
+fn test_de_unknown_property() {
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Strict {
+        a: u8,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Address {
+        port: u16,
+    }
+
+    #[derive(Deserialize)]
+    struct Main {
+        address: Address,
+    }
+
+    let config = Config::parse("a = 1\n".as_bytes()).unwrap();
+    assert_eq!(from_config::<Strict>(&config).unwrap().a, 1);
+
+    let config = Config::parse("address = {port = 80}\n".as_bytes()).unwrap();
+    assert_eq!(from_config::<Main>(&config).unwrap().address.port, 80);
+
+    let config = Config::parse("a = 1\nb = 2\n".as_bytes()).unwrap();
+    match from_config::<Strict>(&config) {
+        Err(ConfigError::UnknownProperty(location, name)) => {
+            assert_eq!(location.get_line(), 2);
+            assert_eq!(location.get_column(), 1);
+            assert_eq!(name, "b");
+        }
+        _ => unreachable!(),
+    }
+
+    let config = Config::parse("address = {port = 80, host = localhost}\n".as_bytes()).unwrap();
+    match from_config::<Main>(&config) {
+        Err(ConfigError::UnknownProperty(location, name)) => {
+            assert_eq!(location.get_column(), 23);
+            assert_eq!(name, "host");
+        }
+        _ => unreachable!(),
+    }
+
+    let config = Config::parse("address = {port = 80, port = 81}\n".as_bytes()).unwrap();
+    match from_config::<Main>(&config) {
+        Err(ConfigError::DuplicateProperty(location, name)) => {
+            assert_eq!(location.get_column(), 23);
+            assert_eq!(name, "port");
+        }
+        _ => unreachable!(),
+    }
+
+    let config = Config::parse("a = test\n".as_bytes()).unwrap();
+    assert!(matches!(from_config::<Strict>(&config), Err(ConfigError::InvalidProperty(..))));
+}
+
+
+#[test]
+fn test_de_diagnostic() {
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Main {
+        #[allow(dead_code)]
+        tsid: u16,
+    }
+
+    let src = "tsid = 1\nname = Main\n";
+    let config = Config::parse(src.as_bytes()).unwrap();
+    let error = from_config::<Main>(&config).err().unwrap();
+
+    assert_eq!(Diagnostic::new(&error, src).to_string(), "error: unknown property 'name'
+ --> line 2:1
+  |
+1 | tsid = 1
+2 | name = Main
+  | ^^^^^^^^^^^
+  = hint: remove 'name' or check spelling of the name
+");
+}
@@ -0,0 +1,110 @@
+use std::fs;
+
+use config::{
+    Config,
+    ConfigError,
+    Diagnostic,
+    Loader,
+    Location,
+};
+
+
+fn parse_error(src: &str) -> ConfigError {
+    Config::parse(src.as_bytes()).err().unwrap()
+}
+
+
+#[test]
+fn test_diagnostic_file() {
+    let error = Config::open("tests/data/include/bad.conf").err().unwrap();
+    let src = fs::read_to_string("tests/data/include/bad-include.conf").unwrap();
+
+    assert_eq!(Diagnostic::new(&error, &src).to_string(), "error: invalid format
+ --> tests/data/include/bad-include.conf:2:1
+  |
+1 | ok = true
+2 | wrong-format
+  | ^^^^^^^^^^^^
+  = hint: expected `name = value`, `[section]` or `@include pattern`
+");
+}
+
+
+#[test]
+fn test_diagnostic_context() {
+    let src = "a = 1\n\tb = ${c}\nd = 2\n";
+    let error = parse_error(src);
+
+    let mut report = Diagnostic::new(&error, src);
+    report.set_context(0);
+    assert_eq!(report.to_string(), "error: undefined reference 'c'
+ --> line 2:6
+  |
+2 | \tb = ${c}
+  | \t    ^^^^
+  = hint: define property or environment variable 'c' or use default value `${c:-...}`
+");
+
+    let src = "a = 1\nb = 2\nc = 3\nd = 4\ne = 5\nf = 6\ng = 7\nh = 8\ni = 9\nj = ${unknown}\nk = 11\n";
+    let mut loader = Loader::new();
+    loader.set_env("HOME", "/root");
+    let error = loader.parse(src.as_bytes()).err().unwrap();
+
+    let mut report = Diagnostic::new(&error, src);
+    report.set_context(1);
+    assert_eq!(report.to_string(), "error: undefined reference 'unknown'
+  --> line 10:5
+   |
+ 9 | i = 9
+10 | j = ${unknown}
+   |     ^^^^^^^^^^
+11 | k = 11
+   = hint: define property or environment variable 'unknown' or use default value `${unknown:-...}`
+");
+}
+
+
+#[test]
+fn test_diagnostic_color() {
+    let src = "[a]\n[b/c]";
+    let error = parse_error(src);
+
+    let mut report = Diagnostic::new(&error, src);
+    report.set_color(true);
+    assert_eq!(report.to_string(), "\x1b[1;31merror\x1b[0m: \x1b[1minvalid key 'b/c'\x1b[0m
+\x1b[1;34m -->\x1b[0m line 2:2
+\x1b[1;34m  |\x1b[0m
+\x1b[1;34m1 |\x1b[0m [a]
+\x1b[1;34m2 |\x1b[0m [b/c]
+\x1b[1;34m  |\x1b[0m  \x1b[1;31m^^^\x1b[0m
+\x1b[1;34m  =\x1b[0m \x1b[1mhint\x1b[0m: nested section must follow its parent [b]
+");
+}
+
+
+#[test]
+fn test_diagnostic_io() {
+    let error = Config::open("tests/data/not-found.conf").err().unwrap();
+    let report = Diagnostic::new(&error, "").to_string();
+    assert!(report.starts_with("error: "));
+    assert!(! report.contains("Config: "), "{}", report);
+    assert_eq!(report.lines().count(), 1);
+}
+
+
+#[test]
+fn test_diagnostic_hint() {
+    let error = ConfigError::InvalidKey(Location::default(), "name".to_owned());
+    let report = Diagnostic::new(&error, "").to_string();
+    assert!(! report.contains("hint"), "{}", report);
+
+    let error = ConfigError::InvalidKey(Location::default(), "a/b".to_owned());
+    let report = Diagnostic::new(&error, "").to_string();
+    assert!(report.contains("= hint: nested section must follow its parent [a]"), "{}", report);
+
+    let src = "[a]\n[b]\n[a/c]\n";
+    let error = parse_error(src);
+    let report = Diagnostic::new(&error, src).to_string();
+    assert!(report.contains("invalid key 'a/c'"), "{}", report);
+    assert!(report.contains("= hint: nested section must follow its parent [a]"), "{}", report);
+}
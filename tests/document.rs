@@ -0,0 +1,155 @@
+use config::{
+    Config,
+    Document,
+};
+
+mod common;
+use common::dump;
+
+
+const SRC: &str = r#"# Main config
+xmltv   = /projects/opt/discovery.xml   # EPG
+output  = udp://127.0.0.1:10000
+
+; deprecated comment
+[multiplex]    # first
+    tsid = 1
+
+    # services
+    [multiplex/service]
+    name = "Channel #1"
+    pnr  = 1
+
+[multiplex/service]
+pnr = 1185
+sdp = <<EOF
+v=0
+EOF
+"#;
+
+
+#[test]
+fn test_document_lossless() {
+    let document = Document::parse(SRC.as_bytes()).unwrap();
+    let mut s = Vec::<u8>::new();
+    document.dump(&mut s).unwrap();
+    assert_eq!(s.as_slice(), SRC.as_bytes());
+
+    let config = document.into_config().unwrap();
+    assert_eq!(dump(&config), SRC);
+}
+
+
+#[test]
+fn test_document_crlf() {
+    let src = "a = 1\r\n\r\n[s]\r\nb = 2";
+    let config = Config::parse(src.as_bytes()).unwrap();
+    assert_eq!(dump(&config), src);
+}
+
+
+#[test]
+fn test_document_edit() {
+    let mut config = Config::parse(SRC.as_bytes()).unwrap();
+
+    config.remove("output");
+    config.set("u16", 1234);
+
+    let mut m = Config::new("multiplex");
+    m.set("tsid", 2);
+    config.push(m);
+
+    assert_eq!(dump(&config), r#"# Main config
+xmltv   = /projects/opt/discovery.xml   # EPG
+u16 = 1234
+
+; deprecated comment
+[multiplex]    # first
+    tsid = 1
+
+    # services
+    [multiplex/service]
+    name = "Channel #1"
+    pnr  = 1
+
+[multiplex/service]
+pnr = 1185
+sdp = <<EOF
+v=0
+EOF
+
+[multiplex]
+tsid = 2
+"#);
+}
+
+
+#[test]
+fn test_document_append_without_newline() {
+    let mut config = Config::parse("a = 1".as_bytes()).unwrap();
+    config.set("b", 2);
+    assert_eq!(dump(&config), "a = 1\nb = 2\n");
+}
+
+
+#[test]
+fn test_document_mutate() {
+    let mut config = Config::parse(SRC.as_bytes()).unwrap();
+
+    config.replace("output", "udp://239.255.1.1:1234");
+    config.replace("u16", 1234);
+    assert!(config.rename("xmltv", "epg"));
+    assert!(! config.rename("not-found", "epg"));
+
+    let multiplex = config.nested_mut("multiplex").unwrap();
+    multiplex.get_property_mut("tsid").unwrap().set_value(2);
+    multiplex.set_name("mux");
+    assert_eq!(multiplex.remove_section("service").unwrap().get("pnr"), Some(1u8));
+
+    for service in multiplex.iter_mut() {
+        service.retain(|p| p.get_value() != "1185");
+    }
+
+    let output = config.get_property("output").unwrap();
+    assert_eq!(output.get_line(), 3);
+    assert_eq!(output.get_value(), "udp://239.255.1.1:1234");
+
+    assert_eq!(dump(&config), r#"# Main config
+epg   = /projects/opt/discovery.xml   # EPG
+output  = udp://239.255.1.1:1234
+u16 = 1234
+
+; deprecated comment
+[mux]    # first
+    tsid = 2
+
+    # services
+[mux/service]
+sdp = <<EOF
+v=0
+EOF
+"#);
+}
+
+
+#[test]
+fn test_document_foreign_section() {
+    let mut a = Config::parse("# c\n[a]\nx = 1\n".as_bytes()).unwrap();
+    let mut b = Config::parse("[b]\ny = 2\n".as_bytes()).unwrap();
+
+    a.remove_section("a");
+    a.push(b.remove_section("b").unwrap());
+    assert_eq!(dump(&a), "# c\n\n[b]\ny = 2\n");
+
+    let reloaded = Config::parse(dump(&a).as_bytes()).unwrap();
+    let section = reloaded.iter().next().unwrap();
+    assert_eq!(section.get_name(), "b");
+    assert_eq!(section.get("y"), Some(2u8));
+    assert_eq!(reloaded.get::<u8>("y"), None);
+
+    let mut a = Config::parse("[a]\nx = 1\n[a/b]\n".as_bytes()).unwrap();
+    let mut b = Config::parse("[b]\n[b/c]\nz = 3\n".as_bytes()).unwrap();
+    let nested = b.iter_mut().next().unwrap().remove_section("c").unwrap();
+    a.iter_mut().next().unwrap().push(nested);
+    assert_eq!(dump(&a), "[a]\nx = 1\n[a/b]\n\n[a/c]\nz = 3\n");
+}
@@ -0,0 +1,128 @@
+use std::{
+    fs,
+    path::Path,
+};
+
+use config::{
+    Config,
+    ConfigError,
+};
+
+
+#[test]
+fn test_include() {
+    let config = Config::open("tests/data/include/main.conf").unwrap();
+    assert_eq!(config.get("xmltv"), Some("/opt/discovery.xml"));
+
+    let multiplex = config.iter().next().unwrap();
+    assert_eq!(multiplex.get("tsid"), Some(1u16));
+    assert_eq!(multiplex.get("comment"), Some("leading"));
+    assert_eq!(multiplex.get("name"), Some("Main"));
+    assert_eq!(multiplex.get_file(), Some(Path::new("tests/data/include/main.conf")));
+
+    let services: Vec<&Config> = multiplex.iter().collect();
+    assert_eq!(services.len(), 2);
+    assert_eq!(services[0].get("pnr"), Some(1u16));
+    assert_eq!(services[0].get_file(), Some(Path::new("tests/data/include/services/a.conf")));
+    assert_eq!(services[1].get("pnr"), Some(2u16));
+    assert_eq!(services[1].get_line(), 3);
+
+    let pnr = services[1].get_property("pnr").unwrap();
+    assert_eq!(pnr.get_file(), Some(Path::new("tests/data/include/services/b.conf")));
+    assert_eq!(pnr.get_line(), 4);
+}
+
+
+#[test]
+fn test_include_dump() {
+    let mut config = Config::open("tests/data/include/main.conf").unwrap();
+    config.set("u16", 1234);
+
+    let mut s = Vec::<u8>::new();
+    config.dump(&mut s).unwrap();
+
+    let main = fs::read_to_string("tests/data/include/main.conf").unwrap();
+    let main = main.replace("discovery.xml\n", "discovery.xml\nu16 = 1234\n");
+    assert_eq!(s.as_slice(), main.as_bytes());
+}
+
+
+#[test]
+fn test_include_dump_changed() {
+    fn dump_error(config: &Config) -> (String, usize, String) {
+        let mut s = Vec::<u8>::new();
+        match config.dump(&mut s) {
+            Err(ConfigError::IncludeChanged(location, name)) => {
+                assert!(s.is_empty());
+                let file = location.get_file().unwrap().display().to_string();
+                (file, location.get_line(), name)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    let mut config = Config::open("tests/data/include/main.conf").unwrap();
+    let multiplex = config.iter_mut().next().unwrap();
+    multiplex.iter_mut().nth(1).unwrap().set("name", "Channel 2");
+    assert_eq!(dump_error(&config), ("tests/data/include/services/b.conf".to_owned(), 3, "name".to_owned()));
+
+    let mut config = Config::open("tests/data/include/main.conf").unwrap();
+    let multiplex = config.iter_mut().next().unwrap();
+    multiplex.iter_mut().next().unwrap().replace("pnr", 10);
+    assert_eq!(dump_error(&config), ("tests/data/include/services/a.conf".to_owned(), 2, "pnr".to_owned()));
+
+    let mut config = Config::open("tests/data/include/main.conf").unwrap();
+    let multiplex = config.iter_mut().next().unwrap();
+    multiplex.remove("comment");
+    assert_eq!(dump_error(&config).2, "comment");
+
+    let mut config = Config::open("tests/data/include/main.conf").unwrap();
+    let multiplex = config.iter_mut().next().unwrap();
+    multiplex.remove_section("service");
+    assert_eq!(dump_error(&config).2, "multiplex/service");
+
+    let mut config = Config::open("tests/data/include/main.conf").unwrap();
+    let multiplex = config.iter_mut().next().unwrap();
+    multiplex.replace("name", "Updated");
+    multiplex.set("tsid", 2);
+    let mut s = Vec::<u8>::new();
+    config.dump(&mut s).unwrap();
+    let s = String::from_utf8(s).unwrap();
+    assert!(s.contains("name = Updated\n"));
+    assert!(s.contains("tsid = 2\n"));
+}
+
+
+#[test]
+fn test_include_cycle() {
+    match Config::open("tests/data/include/cycle-a.conf") {
+        Err(ConfigError::IncludeCycle(location, _)) => {
+            assert_eq!(location.get_file(), Some(Path::new("tests/data/include/cycle-b.conf")));
+            assert_eq!(location.get_line(), 2);
+        }
+        _ => unreachable!(),
+    }
+}
+
+
+#[test]
+fn test_include_error_location() {
+    match Config::open("tests/data/include/bad.conf") {
+        Err(e) => {
+            assert_eq!(e.to_string(), "Config: invalid format at tests/data/include/bad-include.conf:2");
+        }
+        _ => unreachable!(),
+    }
+}
+
+
+#[test]
+fn test_include_not_found() {
+    match Config::parse("@include tests/data/include/not-found.conf".as_bytes()) {
+        Err(ConfigError::InvalidInclude(location, pattern)) => {
+            assert_eq!(location.get_line(), 1);
+            assert_eq!(pattern, "tests/data/include/not-found.conf");
+        }
+        _ => unreachable!(),
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+
+use config::{
+    Config,
+    ConfigError,
+    Loader,
+    Value,
+};
+
+mod common;
+use common::dump;
+
+
+const SRC: &str = r#"pids = [100, 101, 0x200] # elementary streams
+langs = {rus = 1, eng = 2}
+names = ["first, second", "[x]", third,]
+empty = []
+quoted = "[100]"
+"#;
+
+
+#[test]
+fn test_inline_array() {
+    let config = Config::parse(SRC.as_bytes()).unwrap();
+
+    assert_eq!(config.try_get::<Vec<u16>>("pids").unwrap(), Some(vec![100, 101, 0x200]));
+    assert_eq!(config.get::<Vec<&str>>("names").unwrap(), ["first, second", "[x]", "third"]);
+    assert!(config.get::<Vec<u16>>("empty").unwrap().is_empty());
+
+    let property = config.get_property("pids").unwrap();
+    assert_eq!(property.get_value(), "[100, 101, 0x200]");
+    assert_eq!(property.get_comment(), Some("elementary streams"));
+
+    match property.get_structured() {
+        Value::Array(items) => {
+            assert_eq!(items.len(), 3);
+            assert_eq!(items[2].get_name(), "pids");
+            assert_eq!(items[2].get_value(), "0x200");
+            assert_eq!(items[2].get_value_location().get_column(), 19);
+            assert_eq!(items[2].get_value_span(), 18 .. 23);
+        }
+        _ => unreachable!(),
+    }
+
+    /* quoted value is a string */
+    assert_eq!(config.get("quoted"), Some("[100]"));
+    assert!(matches!(config.get_property("quoted").unwrap().get_structured(), Value::Scalar));
+    assert!(config.try_get::<Vec<u16>>("quoted").is_err());
+}
+
+
+#[test]
+fn test_inline_map() {
+    let config = Config::parse(SRC.as_bytes()).unwrap();
+
+    let langs: HashMap<String, u8> = config.require("langs").unwrap();
+    assert_eq!(langs.len(), 2);
+    assert_eq!(langs["rus"], 1);
+    assert_eq!(langs["eng"], 2);
+
+    match config.get_property("langs").unwrap().get_structured() {
+        Value::Map(items) => {
+            assert_eq!(items[1].get_name(), "eng");
+            assert_eq!(items[1].get_column(), 19);
+            assert_eq!(items[1].get_value_location().get_column(), 25);
+        }
+        _ => unreachable!(),
+    }
+
+    assert!(config.try_get::<HashMap<String, u8>>("pids").is_err());
+    assert!(config.try_get::<Vec<u8>>("langs").is_err());
+}
+
+
+#[test]
+fn test_inline_invalid_item() {
+    let config = Config::parse("pids = [100, 101, 0x20000]\n".as_bytes()).unwrap();
+    match config.try_get::<Vec<u16>>("pids") {
+        Err(ConfigError::OutOfRange(location, name)) => {
+            assert_eq!(location.get_line(), 1);
+            assert_eq!(location.get_column(), 19);
+            assert_eq!(name, "pids");
+        }
+        _ => unreachable!(),
+    }
+}
+
+
+#[test]
+fn test_inline_syntax_error() {
+    for src in [
+        "pids = [100, 101\n",
+        "pids = [100,, 101]\n",
+        "pids = [100] 101\n",
+        "pids = [\"100\" 101]\n",
+        "langs = {rus}\n",
+        "langs = {rus = 1, = 2}\n",
+        "langs = {rus = 1\n",
+    ] {
+        assert!(matches!(Config::parse(src.as_bytes()), Err(ConfigError::InvalidFormat(..))), "{}", src);
+    }
+}
+
+
+#[test]
+fn test_inline_dump() {
+    let mut config = Config::parse(SRC.as_bytes()).unwrap();
+    assert_eq!(dump(&config), SRC);
+
+    config.get_property_mut("pids").unwrap().set_array([1, 2]);
+    config.get_property_mut("langs").unwrap().set_value("none");
+    config.set_array("filters", ["pnr=1", "", "a,b"]);
+    config.set_map("map", [("a", "1"), ("b c", "$2"), ("a$b", "3"), ("x#$", "4")]);
+    config.set("text", "[not an array]");
+
+    let result = dump(&config);
+    assert_eq!(result, r#"pids = [1, 2] # elementary streams
+langs = none
+names = ["first, second", "[x]", third,]
+empty = []
+quoted = "[100]"
+filters = ["pnr=1", "", "a,b"]
+map = {a = 1, b c = $$2, a$b = 3, "x#$" = 4}
+text = "[not an array]"
+"#);
+
+    let config = Config::parse(result.as_bytes()).unwrap();
+    assert_eq!(config.get::<Vec<u8>>("pids").unwrap(), [1, 2]);
+    assert_eq!(config.get::<Vec<&str>>("filters").unwrap(), ["pnr=1", "", "a,b"]);
+    let map: HashMap<String, &str> = config.get("map").unwrap();
+    assert_eq!(map["b c"], "$2");
+    assert_eq!(map["a$b"], "3");
+    assert_eq!(map["x#$"], "4");
+    assert_eq!(dump(&config), result);
+    assert_eq!(config.get("text"), Some("[not an array]"));
+}
+
+
+#[test]
+fn test_inline_interpolate() {
+    let mut loader = Loader::new();
+    loader.set_env("HOST_IP", "127.0.0.1");
+    let src = "port = 10000\noutputs = [udp://${HOST_IP}:${port}, \"$${HOST_IP}\"]\ncopy = ${outputs}\n";
+    let config = loader.parse(src.as_bytes()).unwrap();
+
+    assert_eq!(config.get::<Vec<&str>>("outputs").unwrap(), ["udp://127.0.0.1:10000", "${HOST_IP}"]);
+    assert_eq!(config.get("copy"), Some("[udp://127.0.0.1:10000, \"$${HOST_IP}\"]"));
+    assert_eq!(dump(&config), src);
+}
@@ -0,0 +1,126 @@
+use config::{
+    Config,
+    ConfigError,
+    Loader,
+};
+
+mod common;
+use common::dump;
+
+
+fn load(src: &str) -> Result<Config, ConfigError> {
+    let mut loader = Loader::new();
+    loader.set_env("HOST_IP", "127.0.0.1");
+    loader.set_env("EMPTY", "");
+    loader.parse(src.as_bytes())
+}
+
+
+#[test]
+fn test_interpolate_env() {
+    let config = load(r#"
+output = udp://${HOST_IP}:10000
+quoted = "${HOST_IP}"
+"#).unwrap();
+
+    assert_eq!(config.get("output"), Some("udp://127.0.0.1:10000"));
+    assert_eq!(config.get("quoted"), Some("127.0.0.1"));
+}
+
+
+#[test]
+fn test_interpolate_property() {
+    let config = load(r#"
+base = /opt
+port = ${base_port}1
+base_port = 1000
+[multiplex]
+xmltv = ${base}/epg.xml
+[multiplex/service]
+sdp = ${multiplex/xmltv}
+"#).unwrap();
+
+    assert_eq!(config.get("port"), Some(10001u16));
+
+    let multiplex = config.iter().next().unwrap();
+    assert_eq!(multiplex.get("xmltv"), Some("/opt/epg.xml"));
+
+    let service = multiplex.iter().next().unwrap();
+    assert_eq!(service.get("sdp"), Some("/opt/epg.xml"));
+}
+
+
+#[test]
+fn test_interpolate_default() {
+    let config = load(r#"
+a = ${UNDEFINED:-default}
+b = ${EMPTY:-${HOST_IP}}
+c = ${HOST_IP:-default}
+d = ${UNDEFINED:-}
+"#).unwrap();
+
+    assert_eq!(config.get("a"), Some("default"));
+    assert_eq!(config.get("b"), Some("127.0.0.1"));
+    assert_eq!(config.get("c"), Some("127.0.0.1"));
+    assert_eq!(config.get("d"), Some(""));
+}
+
+
+#[test]
+fn test_interpolate_escape() {
+    let config = load(r#"
+a = $${HOST_IP}
+b = price $5
+sdp = <<EOF
+${HOST_IP}
+EOF
+"#).unwrap();
+
+    assert_eq!(config.get("a"), Some("${HOST_IP}"));
+    assert_eq!(config.get("b"), Some("price $5"));
+    assert_eq!(config.get("sdp"), Some("${HOST_IP}"));
+}
+
+
+#[test]
+fn test_interpolate_undefined() {
+    match load("a = 1\nb = ${UNDEFINED}") {
+        Err(ConfigError::UndefinedReference(location, name)) => {
+            assert_eq!(location.get_line(), 2);
+            assert_eq!(name, "UNDEFINED");
+        }
+        _ => unreachable!(),
+    }
+}
+
+
+#[test]
+fn test_interpolate_cycle() {
+    match load("a = ${b}\nb = ${a}") {
+        Err(ConfigError::ReferenceCycle(location, name)) => {
+            assert_eq!(location.get_line(), 1);
+            assert_eq!(name, "a");
+        }
+        _ => unreachable!(),
+    }
+
+    match load("a = ${HOST_IP") {
+        Err(ConfigError::InvalidFormat(location)) => assert_eq!(location.get_line(), 1),
+        _ => unreachable!(),
+    }
+}
+
+
+#[test]
+fn test_interpolate_dump() {
+    let src = "output = udp://${HOST_IP}:10000\nprice = $$5\n";
+    let mut config = load(src).unwrap();
+    assert_eq!(dump(&config), src);
+
+    config.set("cost", "$10");
+    assert_eq!(dump(&config), "output = udp://${HOST_IP}:10000\nprice = $$5\ncost = $$10\n");
+
+    let mut config = Config::new("");
+    config.set("a", "\"$\"");
+    assert_eq!(dump(&config), "a = \"\\\"$$\\\"\"\n");
+}
@@ -0,0 +1,90 @@
+use config::{
+    Config,
+    ConfigError,
+    Location,
+    Schema,
+};
+
+
+const SRC: &str = "# test\nxmltv  = /opt/discovery.xml\n[multiplex]\n  name = \"Канал\" # comment\n  sdp = <<EOF\nv=0\nEOF\n";
+
+
+fn error_location(src: &str) -> Location {
+    match Config::parse(src.as_bytes()) {
+        Err(ConfigError::InvalidFormat(location)) => location,
+        Err(ConfigError::InvalidKey(location, _)) => location,
+        Err(ConfigError::InvalidInclude(location, _)) => location,
+        Err(ConfigError::UndefinedReference(location, _)) => location,
+        _ => unreachable!(),
+    }
+}
+
+
+#[test]
+fn test_property_span() {
+    let config = Config::parse(SRC.as_bytes()).unwrap();
+
+    let xmltv = config.get_property("xmltv").unwrap();
+    assert_eq!(xmltv.get_column(), 1);
+    assert_eq!(&SRC[xmltv.get_name_span()], "xmltv");
+    assert_eq!(&SRC[xmltv.get_value_span()], "/opt/discovery.xml");
+    assert_eq!(&SRC[xmltv.get_location().get_span()], "xmltv  = /opt/discovery.xml");
+    assert_eq!(xmltv.get_value_location().get_column(), 10);
+
+    let multiplex = config.iter().next().unwrap();
+    assert_eq!(multiplex.get_column(), 2);
+    assert_eq!(&SRC[multiplex.get_span()], "multiplex");
+
+    let name = multiplex.get_property("name").unwrap();
+    assert_eq!(name.get_line(), 4);
+    assert_eq!(name.get_column(), 3);
+    assert_eq!(&SRC[name.get_value_span()], "\"Канал\"");
+
+    let sdp = multiplex.get_property("sdp").unwrap();
+    assert_eq!(&SRC[sdp.get_value_span()], "<<EOF\nv=0\nEOF");
+}
+
+
+#[test]
+fn test_error_span() {
+    let location = error_location("a = 1\n  name value\n");
+    assert_eq!(location.get_line(), 2);
+    assert_eq!(location.get_column(), 3);
+    assert_eq!(location.get_span(), 8 .. 18);
+
+    let location = error_location("a = \"Канал\" tail");
+    assert_eq!(location.get_column(), 5);
+
+    let location = error_location("a = 1 \\\n    \"b\" tail\n");
+    assert_eq!(location.get_line(), 2);
+    assert_eq!(location.get_column(), 5);
+
+    let location = error_location("[a]\n[b/c]\n");
+    assert_eq!(location.get_line(), 2);
+    assert_eq!(location.get_column(), 2);
+    assert_eq!(location.get_span(), 5 .. 8);
+
+    let location = error_location("@include  not-found.conf # comment");
+    assert_eq!(location.get_column(), 11);
+    assert_eq!(location.get_span(), 10 .. 24);
+
+    let location = error_location("a = 1\nb = ${c}");
+    assert_eq!(location.get_line(), 2);
+    assert_eq!(location.get_column(), 5);
+    assert_eq!(location.get_span(), 10 .. 14);
+}
+
+
+#[test]
+fn test_invalid_property_span() {
+    let config = Config::parse("tsid = 0xZZ".as_bytes()).unwrap();
+    let mut schema = Schema::new("", "");
+    schema.set("tsid", "", true, Schema::range(1 .. 8191));
+    match schema.check(&config) {
+        Err(ConfigError::InvalidProperty(location, _)) => {
+            assert_eq!(location.get_column(), 8);
+            assert_eq!(location.get_span(), 7 .. 11);
+        }
+        _ => unreachable!(),
+    }
+}
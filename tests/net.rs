@@ -0,0 +1,137 @@
+use std::net::{
+    IpAddr,
+    Ipv4Addr,
+    Ipv6Addr,
+    SocketAddr,
+};
+
+use config::{
+    Cidr,
+    Config,
+    ConfigError,
+    Schema,
+    StreamUrl,
+};
+
+
+const SRC: &str = r#"
+bind = 0.0.0.0:8000
+addr = 239.255.1.1
+addr6 = ::1
+allow = 192.168.0.0/16
+host = 10.1.2.3
+input = udp://eth0@239.255.1.1:10000?ttl=4&sync
+source = http://[fd00::1]:8000/play/a001
+invalid = 192.168.0.0/33
+"#;
+
+
+#[test]
+fn test_ip_addr() {
+    let config = Config::parse(SRC.as_bytes()).unwrap();
+    assert_eq!(config.get("bind"), Some(SocketAddr::from(([0, 0, 0, 0], 8000))));
+    assert_eq!(config.get("addr"), Some(Ipv4Addr::new(239, 255, 1, 1)));
+    assert_eq!(config.get("addr6"), Some(Ipv6Addr::LOCALHOST));
+    assert_eq!(config.get("addr6"), Some(IpAddr::V6(Ipv6Addr::LOCALHOST)));
+
+    match config.try_get::<Ipv4Addr>("addr6") {
+        Err(ConfigError::InvalidProperty(location, name)) => {
+            assert_eq!(location.get_line(), 4);
+            assert_eq!(name, "addr6");
+        }
+        _ => unreachable!(),
+    }
+}
+
+
+#[test]
+fn test_cidr() {
+    let config = Config::parse(SRC.as_bytes()).unwrap();
+
+    let allow: Cidr = config.get("allow").unwrap();
+    assert_eq!(allow.get_prefix(), 16);
+    assert!(! allow.contains(&config.get("host").unwrap()));
+    assert!(allow.contains(&"192.168.200.1".parse().unwrap()));
+    assert!(! allow.contains(&"::1".parse().unwrap()));
+    assert_eq!(allow.to_string(), "192.168.0.0/16");
+
+    let host: Cidr = config.get("host").unwrap();
+    assert_eq!(host.get_prefix(), 32);
+
+    let any = Cidr::parse("0.0.0.0/0").unwrap();
+    assert!(any.contains(&"8.8.8.8".parse().unwrap()));
+
+    let net6 = Cidr::parse("fd00::/8").unwrap();
+    assert!(net6.contains(&"fd00::1".parse().unwrap()));
+    assert!(! net6.contains(&"fe80::1".parse().unwrap()));
+
+    assert!(matches!(config.try_get::<Cidr>("invalid"), Err(ConfigError::InvalidProperty(..))));
+    assert!(Cidr::parse("10.0.0.0/+8").is_none());
+}
+
+
+#[test]
+fn test_stream_url() {
+    let config = Config::parse(SRC.as_bytes()).unwrap();
+
+    let input: StreamUrl = config.get("input").unwrap();
+    assert_eq!(input.get_scheme(), "udp");
+    assert_eq!(input.get_interface(), Some("eth0"));
+    assert_eq!(input.get_host(), "239.255.1.1");
+    assert_eq!(input.get_addr(), Some(IpAddr::V4(Ipv4Addr::new(239, 255, 1, 1))));
+    assert_eq!(input.get_port(), Some(10000));
+    assert_eq!(input.get_path(), "");
+    assert_eq!(input.get_option("ttl"), Some("4"));
+    assert_eq!(input.get_option("sync"), Some(""));
+    assert_eq!(input.get_option("unknown"), None);
+    assert_eq!(input.to_string(), "udp://eth0@239.255.1.1:10000?ttl=4&sync");
+
+    let source: StreamUrl = config.get("source").unwrap();
+    assert_eq!(source.get_scheme(), "http");
+    assert_eq!(source.get_interface(), None);
+    assert_eq!(source.get_host(), "fd00::1");
+    assert_eq!(source.get_port(), Some(8000));
+    assert_eq!(source.get_path(), "/play/a001");
+    assert!(source.get_options().is_empty());
+    assert_eq!(source.to_string(), "http://[fd00::1]:8000/play/a001");
+
+    let name = StreamUrl::parse("HTTP://example.com").unwrap();
+    assert_eq!(name.get_scheme(), "http");
+    assert_eq!(name.get_addr(), None);
+    assert_eq!(name.get_port(), None);
+
+    for value in ["239.255.1.1:10000", "udp://", "udp://@239.255.1.1", "udp://host:port", "udp://[::1", "://host"] {
+        assert!(StreamUrl::parse(value).is_none(), "{}", value);
+    }
+
+    assert!(matches!(config.try_get::<StreamUrl>("bind"), Err(ConfigError::InvalidProperty(..))));
+}
+
+
+#[test]
+fn test_net_schema() {
+    let config = Config::parse(SRC.as_bytes()).unwrap();
+
+    let mut schema = Schema::new("", "");
+    schema.set("addr", "Multicast group", true, Schema::multicast());
+    schema.set("input", "Input stream", true, Schema::multicast());
+    schema.set("source", "Source", true, Schema::scheme(&["udp", "rtp", "HTTP"]));
+    schema.check(&config).unwrap();
+
+    let mut schema = Schema::new("", "");
+    schema.set("input", "Input stream", true, Schema::scheme(&["http", "https"]));
+    match schema.check(&config) {
+        Err(ConfigError::InvalidProperty(location, name)) => {
+            assert_eq!(location.get_line(), 7);
+            assert_eq!(name, "input");
+        }
+        _ => unreachable!(),
+    }
+
+    let f = Schema::multicast();
+    assert!(f("239.0.0.1:1234"));
+    assert!(f("ff02::1"));
+    assert!(! f("10.0.0.1"));
+    assert!(! f("http://example.com"));
+    assert!(! f("test"));
+}
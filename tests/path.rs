@@ -0,0 +1,95 @@
+use std::{
+    env,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use config::{
+    Config,
+    ConfigError,
+    PathCheck,
+    Schema,
+};
+
+
+#[test]
+fn test_path() {
+    let config = Config::open("tests/data/path/main.conf").unwrap();
+    assert_eq!(config.get_file(), Some(Path::new("tests/data/path/main.conf")));
+
+    assert_eq!(config.get("xmltv"), Some(PathBuf::from("tests/data/path/epg/discovery.xml")));
+    assert_eq!(config.get("absolute"), Some(PathBuf::from("/opt/astra/astra.log")));
+    assert_eq!(config.get("xmltv"), Some("epg/discovery.xml"));
+
+    if let Some(home) = env::var_os("HOME") {
+        assert_eq!(config.get("home"), Some(PathBuf::from(home).join("astra")));
+    }
+
+    let service = config.iter().next().unwrap();
+    let playlist: PathBuf = service.get("playlist").unwrap();
+    assert_eq!(playlist, Path::new("tests/data/path/conf.d/../epg/discovery.xml"));
+    assert!(playlist.is_file());
+    assert_eq!(service.get("script"), Some(PathBuf::from("tests/data/path/conf.d/service.lua")));
+}
+
+
+#[test]
+fn test_path_parse() {
+    let config = Config::parse("xmltv = epg.xml\nempty = \"\"\n".as_bytes()).unwrap();
+    assert_eq!(config.get("xmltv"), Some(PathBuf::from("epg.xml")));
+
+    match config.try_get::<PathBuf>("empty") {
+        Err(ConfigError::InvalidProperty(location, name)) => {
+            assert_eq!(location.get_line(), 2);
+            assert_eq!(name, "empty");
+        }
+        _ => unreachable!(),
+    }
+}
+
+
+#[test]
+fn test_path_schema() {
+    let config = Config::open("tests/data/path/main.conf").unwrap();
+
+    let mut service = Schema::new("service", "");
+    service.set_path("playlist", "Playlist", true, PathCheck::File);
+    service.set_path("script", "Script", true, PathCheck::Any);
+
+    let mut schema = Schema::new("", "");
+    schema.set_path("xmltv", "EPG file", true, PathCheck::File);
+    schema.set_path("scripts", "Scripts directory", true, PathCheck::Dir);
+    schema.set_path("missing", "Not checked", true, PathCheck::Any);
+    schema.push(service);
+    schema.check(&config).unwrap();
+
+    let mut schema = Schema::new("", "");
+    schema.set_path("missing", "EPG file", true, PathCheck::Exists);
+    match schema.check(&config) {
+        Err(ConfigError::InvalidProperty(location, name)) => {
+            assert_eq!(location.get_file(), Some(Path::new("tests/data/path/main.conf")));
+            assert_eq!(location.get_line(), 6);
+            assert_eq!(name, "missing");
+        }
+        _ => unreachable!(),
+    }
+
+    let mut schema = Schema::new("", "");
+    schema.set_path("xmltv", "EPG directory", true, PathCheck::Dir);
+    assert!(matches!(schema.check(&config), Err(ConfigError::InvalidProperty(..))));
+
+    let mut service = Schema::new("service", "");
+    service.set_path("script", "Script", true, PathCheck::File);
+    let mut schema = Schema::new("", "");
+    schema.push(service);
+    match schema.check(&config) {
+        Err(ConfigError::InvalidProperty(location, name)) => {
+            assert_eq!(location.get_file(), Some(Path::new("tests/data/path/conf.d/service.conf")));
+            assert_eq!(location.get_line(), 3);
+            assert_eq!(name, "script");
+        }
+        _ => unreachable!(),
+    }
+}
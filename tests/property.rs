@@ -1,4 +1,16 @@
-use config::Config;
+use std::{
+    borrow::Cow,
+    num::{
+        NonZeroU16,
+        NonZeroI32,
+    },
+};
+
+use config::{
+    Config,
+    ConfigError,
+    StrictBool,
+};
 
 
 const T1: &str = r#"
@@ -58,6 +70,42 @@ fn test_property_bool_unavail() {
 }
 
 
+#[test]
+fn test_property_bool_words() {
+    for (value, expected) in [
+        ("yes", true), ("On", true), ("1", true), ("ENABLED", true), ("True", true),
+        ("no", false), ("OFF", false), ("0", false), ("Disabled", false), ("FALSE", false),
+    ] {
+        let config = Config::parse(format!("bool = {}", value).as_bytes()).unwrap();
+        assert_eq!(config.get("bool"), Some(expected), "{}", value);
+    }
+
+    for value in ["y", "2", "enable", "\"\""] {
+        let config = Config::parse(format!("bool = {}", value).as_bytes()).unwrap();
+        assert_eq!(config.get::<bool>("bool"), None, "{}", value);
+    }
+}
+
+
+#[test]
+fn test_property_bool_strict() {
+    let config = Config::parse(T1.as_bytes()).unwrap();
+    assert_eq!(config.get("true"), Some(StrictBool(true)));
+    assert_eq!(config.get("false"), Some(StrictBool(false)));
+
+    for value in ["yes", "1", "TRUE", "False"] {
+        let config = Config::parse(format!("bool = {}", value).as_bytes()).unwrap();
+        match config.try_get::<StrictBool>("bool") {
+            Err(ConfigError::InvalidProperty(location, name)) => {
+                assert_eq!(location.get_line(), 1);
+                assert_eq!(name, "bool");
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+
 #[test]
 fn test_property_u8() {
     let config = Config::parse(T1.as_bytes()).unwrap();
@@ -117,3 +165,244 @@ fn test_wrong_level() {
         Err(e) => println!("{}", e),
     }
 }
+
+
+const QV: &str = r##"
+spaces = "  Hello  "
+escape = "line\nnext\ttab \"quoted\" \\ \u{1F43D}"
+hash = "# not a comment"
+empty = ""
+"##;
+
+
+const WQ: &str = r#"
+ok = true
+wrong = "unclosed
+"#;
+
+
+#[test]
+fn test_property_quoted() {
+    let config = Config::parse(QV.as_bytes()).unwrap();
+    assert_eq!(config.get("spaces"), Some("  Hello  "));
+    assert_eq!(config.get("escape"), Some("line\nnext\ttab \"quoted\" \\ 🐽"));
+    assert_eq!(config.get("hash"), Some("# not a comment"));
+    assert_eq!(config.get("empty"), Some(""));
+}
+
+
+#[test]
+fn test_wrong_quote() {
+    match Config::parse(WQ.as_bytes()) {
+        Ok(_) => unreachable!(),
+        Err(e) => println!("{}", e),
+    }
+}
+
+
+const ML: &str = "
+filter = pnr=1 \\
+    pnr=2 \\
+    pnr=3
+sdp = <<EOF
+v=0
+  o=- 0 0 IN IP4 127.0.0.1
+EOF
+next = value
+";
+
+
+#[test]
+fn test_property_continuation() {
+    let config = Config::parse(ML.as_bytes()).unwrap();
+    assert_eq!(config.get("filter"), Some("pnr=1 pnr=2 pnr=3"));
+    assert_eq!(config.get_property("filter").unwrap().get_line(), 2);
+}
+
+
+#[test]
+fn test_property_heredoc() {
+    let config = Config::parse(ML.as_bytes()).unwrap();
+    assert_eq!(config.get("sdp"), Some("v=0\n  o=- 0 0 IN IP4 127.0.0.1"));
+    assert_eq!(config.get_property("sdp").unwrap().get_line(), 5);
+    assert_eq!(config.get("next"), Some("value"));
+    assert_eq!(config.get_property("next").unwrap().get_line(), 9);
+}
+
+
+#[test]
+fn test_wrong_heredoc() {
+    match Config::parse("sdp = <<EOF\nv=0\n".as_bytes()) {
+        Ok(_) => unreachable!(),
+        Err(e) => println!("{}", e),
+    }
+}
+
+
+const IC: &str = r##"
+tsid = 1 # transport id
+name = "Channel #1" # quoted
+url = http://host/#anchor
+[multiplex] # first multiplex
+pnr = 1
+"##;
+
+
+#[test]
+fn test_property_inline_comment() {
+    let config = Config::parse(IC.as_bytes()).unwrap();
+    assert_eq!(config.get("tsid"), Some(1u16));
+    assert_eq!(config.get_property("tsid").unwrap().get_comment(), Some("transport id"));
+    assert_eq!(config.get("name"), Some("Channel #1"));
+    assert_eq!(config.get_property("name").unwrap().get_comment(), Some("quoted"));
+    assert_eq!(config.get("url"), Some("http://host/#anchor"));
+    assert_eq!(config.get_property("url").unwrap().get_comment(), None);
+
+    let multiplex = config.iter().next().unwrap();
+    assert_eq!(multiplex.get_comment(), Some("first multiplex"));
+    assert_eq!(multiplex.get("pnr"), Some(1u16));
+}
+
+
+#[test]
+fn test_wrong_quote_tail() {
+    match Config::parse("name = \"value\" tail".as_bytes()) {
+        Ok(_) => unreachable!(),
+        Err(e) => println!("{}", e),
+    }
+}
+
+
+#[test]
+fn test_property_try_get() {
+    let config = Config::parse(T1.as_bytes()).unwrap();
+    assert_eq!(config.try_get("u8").unwrap(), Some(188u8));
+    assert_eq!(config.try_get::<u8>("not-found").unwrap(), None);
+
+    match config.try_get::<u8>("u8-max") {
+        Err(ConfigError::OutOfRange(location, name)) => {
+            assert_eq!(location.get_line(), 6);
+            assert_eq!(name, "u8-max");
+        }
+        _ => unreachable!(),
+    }
+}
+
+
+#[test]
+fn test_property_require() {
+    let config = Config::parse(T1.as_bytes()).unwrap();
+    assert_eq!(config.require::<&str>("str").unwrap(), "Hello, world!");
+    assert!(matches!(config.require::<bool>("bool"), Err(ConfigError::InvalidProperty(..))));
+
+    match config.require::<u8>("not-found") {
+        Err(ConfigError::MissingProperty(_, name)) => assert_eq!(name, "not-found"),
+        _ => unreachable!(),
+    }
+}
+
+
+#[test]
+fn test_property_get_all() {
+    let src = "pid = 100\nname = test\npid = 0x200\npid = 300\n";
+    let config = Config::parse(src.as_bytes()).unwrap();
+
+    let list: Vec<&str> = config.get_all("pid").map(|p| p.get_value()).collect();
+    assert_eq!(list, ["100", "0x200", "300"]);
+    assert_eq!(config.get_all("not-found").count(), 0);
+
+    assert_eq!(config.try_get_all::<u16>("pid").unwrap(), [100, 0x200, 300]);
+    assert!(config.try_get_all::<u16>("not-found").unwrap().is_empty());
+
+    match config.try_get_all::<u8>("pid") {
+        Err(ConfigError::OutOfRange(location, name)) => {
+            assert_eq!(location.get_line(), 3);
+            assert_eq!(name, "pid");
+        }
+        _ => unreachable!(),
+    }
+}
+
+
+#[test]
+fn test_property_float() {
+    let src = "gain = 1.5\nbitrate = 2.5e6\nsmall = -1E-3\ninteger = 10\ninvalid = 1.5dB\n";
+    let config = Config::parse(src.as_bytes()).unwrap();
+    assert_eq!(config.get("gain"), Some(1.5f32));
+    assert_eq!(config.get("bitrate"), Some(2_500_000f64));
+    assert_eq!(config.get("small"), Some(-0.001f64));
+    assert_eq!(config.get("integer"), Some(10f64));
+
+    match config.try_get::<f64>("invalid") {
+        Err(ConfigError::InvalidProperty(location, name)) => {
+            assert_eq!(location.get_line(), 5);
+            assert_eq!(name, "invalid");
+        }
+        _ => unreachable!(),
+    }
+}
+
+
+#[test]
+fn test_property_char() {
+    let src = "separator = ;\nunicode = \"\\u{2192}\"\nempty = \"\"\nlong = ab\n";
+    let config = Config::parse(src.as_bytes()).unwrap();
+    assert_eq!(config.get("separator"), Some(';'));
+    assert_eq!(config.get("unicode"), Some('\u{2192}'));
+    assert!(matches!(config.try_get::<char>("empty"), Err(ConfigError::InvalidProperty(..))));
+    assert!(matches!(config.try_get::<char>("long"), Err(ConfigError::InvalidProperty(..))));
+}
+
+
+#[test]
+fn test_property_string() {
+    let config = Config::parse(T1.as_bytes()).unwrap();
+    assert_eq!(config.get::<String>("str").unwrap(), "Hello, world!");
+    assert_eq!(&*config.get::<Box<str>>("str").unwrap(), "Hello, world!");
+    assert!(matches!(config.get::<Cow<str>>("str"), Some(Cow::Borrowed("Hello, world!"))));
+}
+
+
+#[test]
+fn test_property_nonzero() {
+    let src = "pnr = 0x10\nzero = 0\ndelta = -5\n";
+    let config = Config::parse(src.as_bytes()).unwrap();
+    assert_eq!(config.get::<NonZeroU16>("pnr").map(NonZeroU16::get), Some(16));
+    assert_eq!(config.get::<NonZeroI32>("delta").map(NonZeroI32::get), Some(-5));
+
+    match config.try_get::<NonZeroU16>("zero") {
+        Err(ConfigError::InvalidProperty(location, name)) => {
+            assert_eq!(location.get_line(), 2);
+            assert_eq!(name, "zero");
+        }
+        _ => unreachable!(),
+    }
+}
+
+
+#[test]
+fn test_property_number_literals() {
+    let src = "hex = 0X1F\nneg-hex = -0x10\noct = 0o755\nbin = 0b1010\nsep = 1_000_000\nplus = +42\nmin = -128\n";
+    let config = Config::parse(src.as_bytes()).unwrap();
+    assert_eq!(config.get("hex"), Some(0x1Fu8));
+    assert_eq!(config.get("neg-hex"), Some(-16i32));
+    assert_eq!(config.get("oct"), Some(0o755u16));
+    assert_eq!(config.get("bin"), Some(10u8));
+    assert_eq!(config.get("sep"), Some(1_000_000u32));
+    assert_eq!(config.get("plus"), Some(42i8));
+    assert_eq!(config.get("min"), Some(-128i8));
+
+    for value in ["", "-", "0x", "_1", "1_", "0b102", "1.0", "0x-10", "--1", "1 000", "0xFF_FF_"] {
+        let config = Config::parse(format!("value = \"{}\"", value).as_bytes()).unwrap();
+        assert!(matches!(config.try_get::<i64>("value"), Err(ConfigError::InvalidProperty(..))), "{}", value);
+    }
+
+    for value in ["256", "-1", "0x100", "0b1_0000_0000", "99999999999999999999999999999999999999999"] {
+        let config = Config::parse(format!("value = {}", value).as_bytes()).unwrap();
+        assert!(matches!(config.try_get::<u8>("value"), Err(ConfigError::OutOfRange(..))), "{}", value);
+    }
+
+    /* syntax error is reported even if number is too large */
+    let config = Config::parse("value = 99999999999999999999999999999999999999999x".as_bytes()).unwrap();
+    assert!(matches!(config.try_get::<u64>("value"), Err(ConfigError::InvalidProperty(..))));
+}
@@ -0,0 +1,88 @@
+use config::{
+    Config,
+    ConfigError,
+};
+
+
+const SRC: &str = r#"
+name = main
+
+[multiplex]
+name = mux-1
+
+[multiplex/service]
+pnr = 1
+xmltv-id = ch1
+
+[multiplex/service]
+pnr = 1185
+xmltv-id = ch1185
+
+[multiplex]
+name = mux-2
+
+[multiplex/service]
+pnr = 1185
+xmltv-id = other
+
+[output]
+name = out
+"#;
+
+
+fn values(config: &Config, path: &str) -> Vec<String> {
+    config.query(path).unwrap()
+        .map(|item| match item.as_property() {
+            Some(p) => p.get_value().to_owned(),
+            None => format!("[{}]", item.as_section().unwrap().get_line()),
+        })
+        .collect()
+}
+
+
+#[test]
+fn test_query() {
+    let config = Config::parse(SRC.as_bytes()).unwrap();
+
+    assert_eq!(values(&config, "name"), vec!["main"]);
+    assert_eq!(values(&config, "multiplex"), vec!["[4]", "[15]"]);
+    assert_eq!(values(&config, "multiplex/name"), vec!["mux-1", "mux-2"]);
+    assert_eq!(values(&config, "multiplex[1]/name"), vec!["mux-2"]);
+    assert_eq!(values(&config, "multiplex[0]/service[*]"), vec!["[7]", "[11]"]);
+    assert_eq!(values(&config, "multiplex/service[pnr=1185]/xmltv-id"), vec!["ch1185", "other"]);
+    assert_eq!(values(&config, "multiplex[name=mux-2]/service/xmltv-id"), vec!["other"]);
+    assert_eq!(values(&config, "*/name"), vec!["mux-1", "mux-2", "out"]);
+    assert_eq!(values(&config, "multiplex[0]/service[1]/*"), vec!["1185", "ch1185"]);
+    assert!(values(&config, "multiplex[2]/name").is_empty());
+    assert!(values(&config, "not-found").is_empty());
+}
+
+
+#[test]
+fn test_query_mut() {
+    let mut config = Config::parse(SRC.as_bytes()).unwrap();
+
+    for item in config.query_mut("multiplex/service[pnr=1185]/xmltv-id").unwrap() {
+        item.into_property().unwrap().set_value("updated");
+    }
+
+    for item in config.query_mut("multiplex[1]").unwrap() {
+        item.into_section().unwrap().set("tsid", 2);
+    }
+
+    assert_eq!(values(&config, "multiplex/service/xmltv-id"), vec!["ch1", "updated", "updated"]);
+    assert_eq!(values(&config, "multiplex/tsid"), vec!["2"]);
+}
+
+
+#[test]
+fn test_query_invalid() {
+    let config = Config::parse(SRC.as_bytes()).unwrap();
+
+    for path in &["", "multiplex/", "/name", "multiplex[0", "multiplex[x]", "multiplex[=1]", "multiplex[0]name"] {
+        match config.query(path) {
+            Err(ConfigError::InvalidQuery(v)) => assert_eq!(&v, path),
+            _ => unreachable!(),
+        }
+    }
+}
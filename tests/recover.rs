@@ -0,0 +1,84 @@
+use std::path::Path;
+
+use config::{
+    Config,
+    ConfigError,
+};
+
+mod common;
+use common::dump;
+
+
+const SRC: &str = r#"a = 1
+wrong-format
+b = "unterminated
+[multiplex]
+tsid = 1
+[wrong/level]
+pnr = 1
+[wrong/level/service]
+pnr = 2
+[multiplex/service]
+pnr = ${undefined}
+@unknown
+name = Service
+"#;
+
+
+#[test]
+fn test_recover() {
+    let (config, errors) = Config::parse_recover(SRC.as_bytes());
+
+    let lines: Vec<usize> = errors.iter().map(|e| match e {
+        ConfigError::InvalidFormat(location) => location.get_line(),
+        ConfigError::InvalidKey(location, _) => location.get_line(),
+        ConfigError::UndefinedReference(location, _) => location.get_line(),
+        _ => unreachable!(),
+    }).collect();
+    assert_eq!(lines, vec![2, 3, 6, 8, 11, 12]);
+
+    assert_eq!(config.get("a"), Some(1u8));
+    assert!(config.get_property("b").is_none());
+
+    let sections: Vec<&Config> = config.iter().collect();
+    assert_eq!(sections.len(), 1);
+    assert_eq!(sections[0].get("tsid"), Some(1u8));
+    assert!(sections[0].get_property("pnr").is_none());
+
+    let service = sections[0].iter().next().unwrap();
+    assert_eq!(service.get("pnr"), Some("${undefined}"));
+    assert_eq!(service.get("name"), Some("Service"));
+
+    assert_eq!(dump(&config), SRC);
+}
+
+
+#[test]
+fn test_recover_valid() {
+    let (config, errors) = Config::parse_recover("a = 1\n[s]\nb = ${a}\n".as_bytes());
+    assert!(errors.is_empty());
+    assert_eq!(config.iter().next().unwrap().get("b"), Some(1u8));
+}
+
+
+#[test]
+fn test_recover_include() {
+    let (config, errors) = Config::open_recover("tests/data/include/bad.conf");
+    assert_eq!(config.get("ok"), Some(true));
+
+    assert_eq!(errors.len(), 1);
+    match &errors[0] {
+        ConfigError::InvalidFormat(location) => {
+            assert_eq!(location.get_file(), Some(Path::new("tests/data/include/bad-include.conf")));
+            assert_eq!(location.get_line(), 2);
+        }
+        _ => unreachable!(),
+    }
+
+    let (_, errors) = Config::open_recover("tests/data/include/cycle-a.conf");
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], ConfigError::IncludeCycle(..)));
+
+    let (_, errors) = Config::open_recover("tests/data/not-found.conf");
+    assert!(matches!(errors[0], ConfigError::Io(..)));
+}
@@ -1,5 +1,8 @@
-use config::Config;
-use config::Schema;
+use config::{
+    Config,
+    ConfigError,
+    Schema,
+};
 
 #[test]
 fn test_schema_range_validator() {
@@ -8,6 +11,10 @@ fn test_schema_range_validator() {
     assert!(f("100"));
     assert!(f("200"));
     assert!(f("0x80"));
+    assert!(f("0X80"));
+    assert!(f("0o200"));
+    assert!(f("0b1000_0000"));
+    assert!(! f("-150"));
     assert!(! f("50"));
     assert!(! f("250"));
     assert!(! f("test"));
@@ -144,3 +151,66 @@ fn test_schema_nested_out_range_unrequred() {
         Err(e) => println!("{}", e),
     }
 }
+
+#[test]
+fn test_schema_repeatable() {
+    let src = "pid = 100\npid = 200\npid = 300\n";
+    let config = Config::parse(src.as_bytes()).unwrap();
+
+    let mut schema = Schema::new("", "");
+    schema.set_repeatable("pid", "PID", 1, 3, Schema::range(1 .. 8191));
+    schema.check(&config).unwrap();
+
+    let mut schema = Schema::new("", "");
+    schema.set_repeatable("pid", "PID", 0, 2, None);
+    match schema.check(&config) {
+        Err(ConfigError::InvalidCount(location, name)) => {
+            assert_eq!(location.get_line(), 3);
+            assert_eq!(name, "pid");
+        }
+        _ => unreachable!(),
+    }
+
+    let mut schema = Schema::new("", "");
+    schema.set_repeatable("pid", "PID", 4, 8, None);
+    assert!(matches!(schema.check(&config), Err(ConfigError::InvalidCount(..))));
+
+    let mut schema = Schema::new("", "");
+    schema.set_repeatable("pid", "PID", 0, 8, Schema::range(1 .. 250));
+    match schema.check(&config) {
+        Err(ConfigError::InvalidProperty(location, _)) => assert_eq!(location.get_line(), 3),
+        _ => unreachable!(),
+    }
+
+    let mut schema = Schema::new("", "");
+    schema.set_repeatable("caid", "CAID", 1, 8, None);
+    assert!(matches!(schema.check(&config), Err(ConfigError::MissingProperty(..))));
+
+    let mut schema = Schema::new("", "");
+    schema.set_repeatable("caid", "CAID", 0, 8, None);
+    schema.check(&config).unwrap();
+}
+
+
+#[test]
+fn test_schema_bool() {
+    let config = Config::parse("debug = yes\nverbose = true\n".as_bytes()).unwrap();
+
+    let mut schema = Schema::new("", "");
+    schema.set_bool("debug", "Debug mode", true, false);
+    schema.set_bool("verbose", "Verbose output", false, true);
+    schema.check(&config).unwrap();
+    assert_eq!(schema.info(), r#"debug = Debug mode (true/yes/on/1/enabled, false/no/off/0/disabled)
+verbose = Verbose output (true, false)
+"#);
+
+    let mut schema = Schema::new("", "");
+    schema.set_bool("debug", "Debug mode", true, true);
+    match schema.check(&config) {
+        Err(ConfigError::InvalidProperty(location, name)) => {
+            assert_eq!(location.get_line(), 1);
+            assert_eq!(name, "debug");
+        }
+        _ => unreachable!(),
+    }
+}
@@ -0,0 +1,159 @@
+#![cfg(feature = "serde")]
+
+use std::collections::BTreeMap;
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use config::{
+    Config,
+    ConfigError,
+    from_config,
+    to_config,
+};
+
+mod common;
+use common::dump;
+
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+struct Service {
+    pnr: u16,
+    name: String,
+    xmltv_id: Option<String>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+enum Mode {
+    DvbT,
+    DvbS,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Multiplex {
+    tsid: u16,
+    mode: Mode,
+    #[serde(default)]
+    service: Vec<Service>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Main {
+    xmltv: String,
+    debug: bool,
+    filter: Vec<u16>,
+    multiplex: Vec<Multiplex>,
+}
+
+
+fn main_config() -> Main {
+    Main {
+        xmltv: "/opt/discovery.xml".to_owned(),
+        debug: true,
+        filter: vec![100, 200],
+        multiplex: vec![
+            Multiplex {
+                tsid: 1,
+                mode: Mode::DvbT,
+                service: vec![
+                    Service { pnr: 1, name: "Channel #1".to_owned(), xmltv_id: None },
+                    Service { pnr: 2, name: "Channel 2".to_owned(), xmltv_id: Some("ch2".to_owned()) },
+                ],
+            },
+            Multiplex {
+                tsid: 2,
+                mode: Mode::DvbS,
+                service: Vec::new(),
+            },
+        ],
+    }
+}
+
+
+#[test]
+fn test_ser() {
+    let config = to_config("", &main_config()).unwrap();
+
+    assert_eq!(dump(&config), r#"xmltv = /opt/discovery.xml
+debug = true
+filter = 100
+filter = 200
+
+[multiplex]
+tsid = 1
+mode = dvb-t
+
+[multiplex/service]
+pnr = 1
+name = "Channel #1"
+
+[multiplex/service]
+pnr = 2
+name = Channel 2
+xmltv-id = ch2
+
+[multiplex]
+tsid = 2
+mode = dvb-s
+service = []
+"#);
+}
+
+
+#[test]
+fn test_ser_round_trip() {
+    let config = to_config("", &main_config()).unwrap();
+    let text = dump(&config);
+
+    let config = Config::parse(text.as_bytes()).unwrap();
+    let main: Main = from_config(&config).unwrap();
+    assert_eq!(main, main_config());
+}
+
+
+#[test]
+fn test_ser_empty() {
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Output {
+        filter: Vec<u16>,
+        service: Vec<Service>,
+        pids: Option<Vec<u16>>,
+    }
+
+    let output = Output {
+        filter: Vec::new(),
+        service: Vec::new(),
+        pids: Some(Vec::new()),
+    };
+
+    let config = to_config("", &output).unwrap();
+    assert_eq!(dump(&config), "filter = []\nservice = []\npids = []\n");
+
+    let config = Config::parse(dump(&config).as_bytes()).unwrap();
+    assert_eq!(from_config::<Output>(&config).unwrap(), output);
+}
+
+
+#[test]
+fn test_ser_map() {
+    let mut map = BTreeMap::new();
+    map.insert("b", "2");
+    map.insert("a", "1");
+
+    let config = to_config("section", &map).unwrap();
+    assert_eq!(config.get_name(), "section");
+    assert_eq!(dump(&config), "a = 1\nb = 2\n");
+}
+
+
+#[test]
+fn test_ser_unsupported() {
+    assert!(matches!(to_config("", &1u8), Err(ConfigError::Custom(_))));
+}
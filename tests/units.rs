@@ -0,0 +1,116 @@
+use std::time::Duration;
+
+use config::{
+    ByteSize,
+    Config,
+    ConfigError,
+    Schema,
+};
+
+
+const SRC: &str = r#"
+timeout = 5s
+interval = 250ms
+session = 1h30m
+fraction = 1.5s
+precise = 1m2s3ms4us5ns
+zero = 0
+buffer = 16MiB
+bitrate = 2M
+packet = 188
+disk = 1.5GiB
+lower = 4kib
+"#;
+
+
+#[test]
+fn test_duration() {
+    let config = Config::parse(SRC.as_bytes()).unwrap();
+    assert_eq!(config.get("timeout"), Some(Duration::from_secs(5)));
+    assert_eq!(config.get("interval"), Some(Duration::from_millis(250)));
+    assert_eq!(config.get("session"), Some(Duration::from_secs(5400)));
+    assert_eq!(config.get("fraction"), Some(Duration::from_millis(1500)));
+    assert_eq!(config.get("precise"), Some(Duration::new(62, 3_004_005)));
+    assert_eq!(config.get("zero"), Some(Duration::ZERO));
+
+    match config.try_get::<Duration>("packet") {
+        Err(ConfigError::InvalidProperty(location, name)) => {
+            assert_eq!(location.get_line(), 10);
+            assert_eq!(name, "packet");
+        }
+        _ => unreachable!(),
+    }
+
+    for value in ["5", "s", "5x", "5 s", "-5s", "1.2.3s", ""] {
+        let config = Config::parse(format!("timeout = \"{}\"", value).as_bytes()).unwrap();
+        assert!(config.try_get::<Duration>("timeout").is_err(), "{}", value);
+    }
+}
+
+
+#[test]
+fn test_duration_set() {
+    let mut config = Config::new("");
+    config.set("session", Duration::from_secs(5400));
+    config.set("fraction", Duration::from_millis(1500));
+    config.set("zero", Duration::ZERO);
+    config.set("precise", Duration::new(90_061, 5));
+
+    assert_eq!(config.get("session"), Some("1h30m"));
+    assert_eq!(config.get("fraction"), Some("1s500ms"));
+    assert_eq!(config.get("zero"), Some("0s"));
+    assert_eq!(config.get("precise"), Some("1d1h1m1s5ns"));
+    assert_eq!(config.get("precise"), Some(Duration::new(90_061, 5)));
+}
+
+
+#[test]
+fn test_byte_size() {
+    let config = Config::parse(SRC.as_bytes()).unwrap();
+    assert_eq!(config.get("buffer"), Some(ByteSize(16 << 20)));
+    assert_eq!(config.get("bitrate"), Some(ByteSize(2_000_000)));
+    assert_eq!(config.get("packet"), Some(ByteSize(188)));
+    assert_eq!(config.get("disk"), Some(ByteSize(3 << 29)));
+    assert_eq!(config.get("lower"), Some(ByteSize(4096)));
+    assert!(matches!(config.try_get::<ByteSize>("timeout"), Err(ConfigError::InvalidProperty(..))));
+
+    assert_eq!(ByteSize(16 << 20).to_string(), "16MiB");
+    assert_eq!(ByteSize(2_000_000).to_string(), "2MB");
+    assert_eq!(ByteSize(3000).to_string(), "3KB");
+    assert_eq!(ByteSize(1536).to_string(), "1536");
+    assert_eq!(ByteSize(1500).to_string(), "1500");
+    assert_eq!(ByteSize(0).to_string(), "0");
+
+    let mut config = Config::new("");
+    config.set("disk", ByteSize(3 << 29));
+    assert_eq!(config.get("disk"), Some("1536MiB"));
+    assert_eq!(config.get("disk"), Some(ByteSize(3 << 29)));
+}
+
+
+#[test]
+fn test_units_schema() {
+    let config = Config::parse(SRC.as_bytes()).unwrap();
+
+    let mut schema = Schema::new("", "");
+    schema.set("timeout", "Timeout", true, Schema::duration(Duration::from_secs(1) ..= Duration::from_secs(3600)));
+    schema.set("buffer", "Buffer size", true, Schema::byte_size(1024 ..= 64 << 20));
+    schema.check(&config).unwrap();
+
+    let mut schema = Schema::new("", "");
+    schema.set("session", "Session", true, Schema::duration(Duration::from_secs(1) ..= Duration::from_secs(3600)));
+    match schema.check(&config) {
+        Err(ConfigError::InvalidProperty(location, name)) => {
+            assert_eq!(location.get_line(), 4);
+            assert_eq!(name, "session");
+        }
+        _ => unreachable!(),
+    }
+
+    let f = Schema::byte_size(1024 ..= 64 << 20);
+    assert!(f("1KiB"));
+    assert!(f("64MiB"));
+    assert!(! f("1000"));
+    assert!(! f("65MiB"));
+    assert!(! f("test"));
+}
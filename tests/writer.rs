@@ -1,6 +1,26 @@
-use std::fs;
+use std::{
+    collections::HashMap,
+    fs,
+    net::{
+        IpAddr,
+        SocketAddr,
+    },
+    num::NonZeroU16,
+    path::PathBuf,
+    time::Duration,
+};
 
-use config::Config;
+use config::{
+    ByteSize,
+    Cidr,
+    Config,
+    Hex,
+    Property,
+    ScalarProperty,
+    StreamUrl,
+    StrictBool,
+    ToProperty,
+};
 
 #[test]
 fn test_writer() {
@@ -34,3 +54,162 @@ fn test_writer() {
     let t1 = fs::read_to_string("tests/data/t1.conf").unwrap();
     assert_eq!(s.as_str(), t1.as_str());
 }
+
+#[test]
+fn test_writer_quoted() {
+    let mut config = Config::new("");
+    config.set("name", " Channel\t#1\n");
+    config.set("plain", "Channel 1");
+
+    let mut s = Vec::<u8>::new();
+    config.dump(&mut s).unwrap();
+    assert_eq!(s.as_slice(), b"name = \" Channel\\t#1\\n\"\nplain = Channel 1\n".as_ref());
+
+    let config = Config::parse(s.as_slice()).unwrap();
+    assert_eq!(config.get("name"), Some(" Channel\t#1\n"));
+    assert_eq!(config.get("plain"), Some("Channel 1"));
+}
+
+#[test]
+fn test_writer_multiline() {
+    let src = "filter = pnr=1 \\\n    pnr=2\nsdp = <<EOF\nv=0\n  o=- 0\nEOF\n";
+    let config = Config::parse(src.as_bytes()).unwrap();
+
+    let mut s = Vec::<u8>::new();
+    config.dump(&mut s).unwrap();
+    assert_eq!(s.as_slice(), b"filter = pnr=1 \\\n    pnr=2\nsdp = <<EOF\nv=0\n  o=- 0\nEOF\n".as_ref());
+
+    let mut config = Config::new("");
+    config.set("path", "C:\\temp\\");
+
+    let mut s = Vec::<u8>::new();
+    config.dump(&mut s).unwrap();
+    assert_eq!(s.as_slice(), b"path = \"C:\\\\temp\\\\\"\n".as_ref());
+}
+
+#[test]
+fn test_writer_continuation() {
+    for value in &["ab\"c", "ab{x", "ab[x", "ab x"] {
+        let mut config = Config::parse("filter = a \\\n  b\n".as_bytes()).unwrap();
+        config.replace("filter", *value);
+
+        let mut s = Vec::<u8>::new();
+        config.dump(&mut s).unwrap();
+
+        let config = Config::parse(s.as_slice()).unwrap();
+        assert_eq!(config.get("filter"), Some(*value));
+    }
+}
+
+#[test]
+fn test_writer_comment() {
+    let src = "tsid = 1 # transport id\n\n[multiplex] # first\nsdp = <<EOF # raw\nv=0\nEOF\n";
+    let config = Config::parse(src.as_bytes()).unwrap();
+
+    let mut s = Vec::<u8>::new();
+    config.dump(&mut s).unwrap();
+    assert_eq!(s.as_slice(), src.as_bytes());
+}
+
+#[test]
+fn test_writer_typed() {
+    let langs: HashMap<String, &str> = [("rus", "Russian"), ("eng", "English, UK")]
+        .iter()
+        .map(|(k, v)| (k.to_string(), *v))
+        .collect();
+
+    let mut config = Config::new("");
+    config.set("timeout", Duration::from_millis(1500));
+    config.set("buffer", ByteSize(16 << 20));
+    config.set("pid", Hex(0x1FFFu16));
+    config.set("offset", Hex(-16i32));
+    config.set("pids", vec![Hex(0x100u16), Hex(0x101)]);
+    config.set("caids", [0x0500u16, 0x4AE1].as_ref());
+    config.set("langs", &langs);
+    config.set("ratio", 0.1f64);
+    config.set("pnr", NonZeroU16::new(1).unwrap());
+    config.set("strict", StrictBool(false));
+    config.set("separator", ',');
+    config.set("addr", "239.255.1.1:10000".parse::<SocketAddr>().unwrap());
+    config.set("allow", Cidr::parse("fd00::/8").unwrap());
+    config.set("input", StreamUrl::parse("udp://eth0@[ff02::1]:10000?ttl=4").unwrap());
+    config.set("xmltv", PathBuf::from("/opt/epg.xml"));
+    config.set("text", "[1, 2]");
+    config.set("cost", "${HOME}");
+
+    let mut s = Vec::<u8>::new();
+    config.dump(&mut s).unwrap();
+    let s = String::from_utf8(s).unwrap();
+    assert_eq!(s.as_str(), r#"timeout = 1s500ms
+buffer = 16MiB
+pid = 0x1FFF
+offset = -0x10
+pids = [0x100, 0x101]
+caids = [1280, 19169]
+langs = {eng = "English, UK", rus = Russian}
+ratio = 0.1
+pnr = 1
+strict = false
+separator = ,
+addr = 239.255.1.1:10000
+allow = fd00::/8
+input = udp://eth0@[ff02::1]:10000?ttl=4
+xmltv = /opt/epg.xml
+text = "[1, 2]"
+cost = $${HOME}
+"#);
+
+    let config = Config::parse(s.as_bytes()).unwrap();
+    assert_eq!(config.get("timeout"), Some(Duration::from_millis(1500)));
+    assert_eq!(config.get("buffer"), Some(ByteSize(16 << 20)));
+    assert_eq!(config.get("pid"), Some(0x1FFFu16));
+    assert_eq!(config.get("offset"), Some(Hex(-16i32)));
+    assert_eq!(config.get("pids"), Some(vec![0x100u16, 0x101]));
+    assert_eq!(config.get("caids"), Some(vec![0x0500u16, 0x4AE1]));
+    assert_eq!(config.get("langs"), Some(langs));
+    assert_eq!(config.get("ratio"), Some(0.1f64));
+    assert_eq!(config.get("pnr"), NonZeroU16::new(1));
+    assert_eq!(config.get("strict"), Some(StrictBool(false)));
+    assert_eq!(config.get("separator"), Some(','));
+    assert_eq!(config.get("addr"), Some("239.255.1.1:10000".parse::<SocketAddr>().unwrap()));
+    assert_eq!(config.get("allow"), Cidr::parse("fd00::/8"));
+    assert_eq!(config.get("input"), StreamUrl::parse("udp://eth0@[ff02::1]:10000?ttl=4"));
+    assert_eq!(config.get("xmltv"), Some(PathBuf::from("/opt/epg.xml")));
+    assert_eq!(config.get("text"), Some("[1, 2]"));
+    assert_eq!(config.get("cost"), Some("${HOME}"));
+}
+
+#[test]
+fn test_writer_hex_limits() {
+    let mut config = Config::new("");
+    config.set("u64", Hex(u64::MAX));
+    config.set("i64", Hex(i64::MIN));
+    config.set("u8", Hex(0u8));
+
+    assert_eq!(config.get("u64"), Some("0xFFFFFFFFFFFFFFFF"));
+    assert_eq!(config.get("u64"), Some(u64::MAX));
+    assert_eq!(config.get("i64"), Some("-0x8000000000000000"));
+    assert_eq!(config.get("i64"), Some(i64::MIN));
+    assert_eq!(config.get("u8"), Some("0x0"));
+}
+
+#[test]
+fn test_writer_custom() {
+    struct Addr(IpAddr);
+
+    impl ToProperty for Addr {
+        fn to_property(&self, p: &mut Property) {
+            p.set_value(self.0.to_string())
+        }
+    }
+
+    impl ScalarProperty for Addr {}
+
+    let mut config = Config::new("");
+    config.set("addr", Addr(IpAddr::from([127, 0, 0, 1])));
+    config.replace("addr", Addr(IpAddr::from([10, 0, 0, 1])));
+    config.set_array("list", [Addr(IpAddr::from([10, 0, 0, 2]))]);
+
+    assert_eq!(config.get("addr"), Some(IpAddr::from([10, 0, 0, 1])));
+    assert_eq!(config.get("list"), Some(vec![IpAddr::from([10, 0, 0, 2])]));
+}